@@ -0,0 +1,398 @@
+//! app.json 转换为 React 应用入口：每个页面一条路由，window 配置与 tabBar 由运行时的 AppLayout 应用
+use std::{fs, io, path::Path};
+use serde_json::{Map, Value};
+use crate::expression::quote_string;
+use crate::mini_to_react::to_camel_case;
+use crate::runtime::{write_runtime, RUNTIME_DIR};
+use crate::script::{parse_script, reindent};
+use crate::style::{compile_style, StyleOptions};
+
+pub const APP_FILE: &str = "App.tsx";
+pub const APP_PROVIDER_FILE: &str = "AppProvider.tsx";
+pub const APP_STYLE_FILE: &str = "app.css";
+
+/// App 生命周期对应的运行时 hook
+const APP_HOOKS: [(&str, &str); 4] = [
+    ("onLaunch", "useLaunch"),
+    ("onShow", "useShow"),
+    ("onHide", "useHide"),
+    ("onError", "useAppError"),
+];
+
+/// 页面 json 中不属于 window 配置的字段
+const PAGE_OPTIONS: [&str; 2] = ["usingComponents", "component"];
+
+fn read_json(path: &Path) -> Value {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    serde_json::from_str(&content).unwrap_or(Value::Null)
+}
+
+fn page_component_name(page: &str) -> String {
+    to_camel_case(&page.replace('/', "-"))
+}
+
+fn page_window(project_root: &Path, page: &str) -> Value {
+    let window = match read_json(&project_root.join(page).with_extension("json")) {
+        Value::Object(map) => map.into_iter().filter(|(key, _)| !PAGE_OPTIONS.contains(&key.as_str())).collect(),
+        _ => Map::new(),
+    };
+    Value::Object(window)
+}
+
+// tabBar 图标复制到目标目录并作为模块导入，其余字段原样输出
+fn tab_bar_config(tab_bar: &Map<String, Value>, project_root: &Path, target_root: &Path, icons: &mut Vec<String>) -> String {
+    let mut fields = vec![];
+    for (key, value) in tab_bar {
+        if key != "items" {
+            fields.push(format!("  {}: {},\n", key, value));
+        }
+    }
+    let mut items = vec![];
+    for item in tab_bar.get("items").and_then(Value::as_array).into_iter().flatten() {
+        let Some(item) = item.as_object() else {
+            continue;
+        };
+        let mut props = vec![];
+        for (key, value) in item {
+            let icon = value.as_str().filter(|_| key == "icon" || key == "activeIcon");
+            let source = icon.map(|icon| project_root.join(icon.trim_start_matches('/')));
+            match (icon, source) {
+                (Some(icon), Some(source)) if source.exists() => {
+                    let icon = icon.trim_start_matches('/');
+                    let target = target_root.join(icon);
+                    if let Some(parent) = target.parent() {
+                        let _ = fs::create_dir_all(parent);
+                    }
+                    if let Err(e) = fs::copy(&source, &target) {
+                        eprintln!("Failed to copy {:?} to {:?}: {}", source, target, e);
+                    }
+                    let name = format!("tabIcon{}", icons.len());
+                    icons.push(format!("import {} from {};\n", name, quote_string(&format!("./{}", icon))));
+                    props.push(format!("{}: {}", key, name));
+                }
+                _ => props.push(format!("{}: {}", key, value)),
+            }
+        }
+        items.push(format!("    {{ {} }},\n", props.join(", ")));
+    }
+    format!("{{\n{}  items: [\n{}  ],\n}}", fields.concat(), items.concat())
+}
+
+/// 将 app.js 转换为 `AppProvider.tsx`，没有 app.js 时返回 false
+///
+/// globalData 等对象字段由 useAppData 保存，与小程序一样可以直接修改，修改后重新渲染；方法和字段组成应用实例，
+/// 通过 AppContext 提供给组件内的 useApp()，并注册给组件外的 getApp()
+fn convert_app_script(project_root: &Path, target_root: &Path) -> io::Result<bool> {
+    let Some(path) = ["app.js", "app.ts"].into_iter().map(|name| project_root.join(name)).find(|path| path.exists())
+    else {
+        return Ok(false);
+    };
+    let script = parse_script(&path).map_err(|reason| io::Error::new(io::ErrorKind::InvalidData, reason))?;
+    for warning in &script.warnings {
+        eprintln!("{:?}: {}", path, warning);
+    }
+
+    let mut runtime_names = vec!["AppContext", "registerApp"];
+    let mut hooks = vec![];
+    let mut uses_ref = script.uses_instance;
+    for field in &script.options {
+        // 对象字段被修改时重新渲染，其它字段保存在 ref 上，修改不会触发渲染
+        if script.ref_fields.contains(&field.name) {
+            uses_ref = true;
+            hooks.push(format!("{}const {} = useRef<any>({});", field.comments, field.name, field.value));
+        } else {
+            if !runtime_names.contains(&"useAppData") {
+                runtime_names.push("useAppData");
+            }
+            hooks.push(format!("{}const {} = useAppData<any>({});", field.comments, field.name, field.value));
+        }
+    }
+    if script.uses_instance {
+        hooks.push("const instance = useRef<Record<string, any>>({}).current;".to_string());
+    }
+    if script.uses_app {
+        runtime_names.push("useApp");
+        hooks.push("const appInstance = useApp();".to_string());
+    }
+    let functions = script.methods.iter().chain(&script.lifecycles).map(|method| method.to_function()).collect::<Vec<_>>();
+    let mut effects = vec![];
+    for lifecycle in &script.lifecycles {
+        if !APP_HOOKS.iter().any(|(name, _)| *name == lifecycle.name) {
+            eprintln!("{:?}: lifecycle is not supported: `{}`", path, lifecycle.name);
+        }
+    }
+    for (name, hook) in APP_HOOKS {
+        if script.lifecycles.iter().any(|method| method.name == name) {
+            runtime_names.push(hook);
+            effects.push(format!("{}({});", hook, name));
+        }
+    }
+    if script.uses_setters || !script.state.is_empty() || script.data_factory.is_some() || !script.data.is_empty() {
+        eprintln!("{:?}: data and setData are not supported in App, use globalData instead", path);
+    }
+    // ref 上的字段通过访问器读写，getApp().x = value 与 this.x = value 修改的是同一个值
+    let members = script
+        .options
+        .iter()
+        .map(|field| match script.ref_fields.contains(&field.name) {
+            true => format!(
+                "get {0}() {{ return {0}.current; }},\n  set {0}(value: any) {{ {0}.current = value; }},",
+                field.name
+            ),
+            false => format!("{},", field.name),
+        })
+        .chain(script.methods.iter().map(|method| format!("{},", method.name)))
+        .collect::<Vec<_>>();
+    match script.ref_fields.is_empty() {
+        true => effects.push(format!("const app = {{ {} }};", members.join(" ").trim_end_matches(','))),
+        false => effects.push(format!("const app = {{\n  {}\n}};", members.join("\n  "))),
+    }
+    effects.push("registerApp(app);".to_string());
+    let mut body = hooks.iter().map(|hook| format!("  {}\n", reindent(hook, 2))).collect::<String>();
+    for function in &functions {
+        if !body.is_empty() {
+            body.push('\n');
+        }
+        body.push_str(&format!("  {}\n", reindent(function, 2)));
+    }
+    if !body.is_empty() {
+        body.push('\n');
+    }
+    body.extend(effects.iter().map(|effect| format!("  {}\n", reindent(effect, 2))));
+
+    let react_import = if !uses_ref {
+        "import React from \"react\";\n"
+    } else {
+        "import React, { useRef } from \"react\";\n"
+    };
+    if script.declarations_use_app {
+        runtime_names.push("getApp");
+    }
+    runtime_names.sort();
+    let runtime_import = format!("import {{ {} }} from \"./{}\";\n", runtime_names.join(", "), RUNTIME_DIR);
+    let imports = script.imports.iter().map(|import| format!("{}\n", import)).collect::<String>();
+    let declarations = script.declarations.iter().map(|declaration| format!("{}\n\n", declaration)).collect::<String>();
+    let content = format!(
+        "{}{}{}
+{}export default function AppProvider({{ children }}: {{ children?: React.ReactNode }}) {{
+{}  return <AppContext.Provider value={{app}}>{{children}}</AppContext.Provider>;
+}}
+",
+        react_import, runtime_import, imports, declarations, body,
+    );
+    fs::write(target_root.join(APP_PROVIDER_FILE), content)?;
+    Ok(true)
+}
+
+/// 编译全局样式 app.acss / app.less 为 app.css，不存在时返回 false
+fn convert_app_style(project_root: &Path, target_root: &Path, style_options: &StyleOptions) -> io::Result<bool> {
+    let candidates = ["app.acss", "app.less"].map(|name| project_root.join(name));
+    let Some(path) = candidates.into_iter().find(|path| path.is_file()) else {
+        return Ok(false);
+    };
+    // 全局样式不使用 CSS Modules
+    let options = StyleOptions { css_modules: false, ..style_options.clone() };
+    let stylesheet = compile_style(&path, &options);
+    for warning in &stylesheet.warnings {
+        eprintln!("{:?}: {}", path, warning);
+    }
+    fs::write(target_root.join(APP_STYLE_FILE), stylesheet.to_css())?;
+    Ok(true)
+}
+
+pub fn convert_app(project_root: &Path, target_root: &Path) -> io::Result<()> {
+    convert_app_with(project_root, target_root, &StyleOptions::default())
+}
+
+/// 生成 `App.tsx`，页面组件由 copy_dependency 转换到目标目录中的同名路径下
+pub fn convert_app_with(project_root: &Path, target_root: &Path, style_options: &StyleOptions) -> io::Result<()> {
+    let app = read_json(&project_root.join("app.json"));
+    if app.is_null() {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "app.json not found or invalid"));
+    }
+    write_runtime(target_root)?;
+    let has_provider = convert_app_script(project_root, target_root)?;
+    let has_style = convert_app_style(project_root, target_root, style_options)?;
+
+    let mut imports = vec![];
+    let mut routes = vec![];
+    let mut first_page = "";
+    let pages = app.get("pages").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str);
+    for page in pages {
+        let page = page.trim_start_matches('/');
+        if !project_root.join(page).with_extension("axml").exists() {
+            eprintln!("{:?}: page not found: `{}`", project_root.join("app.json"), page);
+            continue;
+        }
+        if routes.is_empty() {
+            first_page = page;
+        }
+        let name = page_component_name(page);
+        imports.push(format!("import {} from {};\n", name, quote_string(&format!("./{}", page))));
+        let window = page_window(project_root, page);
+        let window = if window.as_object().is_some_and(Map::is_empty) {
+            String::new()
+        } else {
+            format!(", window: {}", window)
+        };
+        routes.push(format!("  {{ path: {}, component: {}{} }},\n", quote_string(&format!("/{}", page)), name, window));
+    }
+
+    let window = app.get("window").cloned().unwrap_or_else(|| Value::Object(Map::new()));
+    let window = serde_json::to_string_pretty(&window).unwrap_or_default();
+    let mut icons = vec![];
+    let tab_bar = match app.get("tabBar").and_then(Value::as_object) {
+        Some(tab_bar) => format!(
+            "\nconst tabBar: TabBarConfig = {};\n",
+            tab_bar_config(tab_bar, project_root, target_root, &mut icons)
+        ),
+        None => String::new(),
+    };
+    imports.extend(icons);
+    // 第一个页面是首页
+    let home = if first_page.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "\n      <Route path=\"/\" element={{<Navigate to={} replace />}} />",
+            quote_string(&format!("/{}", first_page))
+        )
+    };
+    let layout_props = if tab_bar.is_empty() { "" } else { " tabBar={tabBar}" };
+    let runtime_names = if tab_bar.is_empty() { "AppLayout, PageRoute" } else { "AppLayout, PageRoute, TabBarConfig" };
+
+    let router = format!(
+        "<HashRouter>
+  <AppLayout window={{appWindow}} pages={{pages}}{}>
+    <Routes>{}
+      {{pages.map(({{ path, component: Page }}) => (
+        <Route key={{path}} path={{path}} element={{<Page />}} />
+      ))}}
+    </Routes>
+  </AppLayout>
+</HashRouter>",
+        layout_props, home,
+    );
+    // 存在 app.js 时由 AppProvider 包裹整个应用
+    let router = if has_provider {
+        imports.push("import AppProvider from \"./AppProvider\";\n".to_string());
+        format!("<AppProvider>\n  {}\n</AppProvider>", reindent(&router, 2))
+    } else {
+        router
+    };
+    if has_style {
+        imports.push(format!("import \"./{}\";\n", APP_STYLE_FILE));
+    }
+
+    let content = format!(
+        "import React from \"react\";
+import {{ HashRouter, Navigate, Route, Routes }} from \"react-router-dom\";
+import {{ {} }} from \"./{}/app\";
+{}
+export const pages: PageRoute[] = [
+{}];
+
+const appWindow = {};
+{}
+export default function App() {{
+  return (
+    {}
+  );
+}}
+",
+        runtime_names,
+        RUNTIME_DIR,
+        imports.concat(),
+        routes.concat(),
+        window,
+        tab_bar,
+        reindent(&router, 4),
+    );
+    fs::write(target_root.join(APP_FILE), content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 在临时目录中写入小程序文件并转换，返回生成的 App.tsx、app.css 和 AppProvider.tsx
+    fn convert(name: &str, files: &[(&str, &str)]) -> (String, Option<String>, Option<String>) {
+        let dir = std::env::temp_dir().join(format!("mini2react-{}", std::process::id())).join(name);
+        let project_root = dir.join("src");
+        let target_root = dir.join("out");
+        for (file, content) in files {
+            let path = project_root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        fs::create_dir_all(&target_root).unwrap();
+        convert_app(&project_root, &target_root).unwrap();
+        let app = fs::read_to_string(target_root.join(APP_FILE)).unwrap();
+        let style = fs::read_to_string(target_root.join(APP_STYLE_FILE)).ok();
+        let provider = fs::read_to_string(target_root.join(APP_PROVIDER_FILE)).ok();
+        fs::remove_dir_all(&dir).unwrap();
+        (app, style, provider)
+    }
+
+    #[test]
+    fn pages_become_routes_with_tab_bar() {
+        let app_json = r#"{
+  "pages": ["pages/index/index", "pages/user-center/index", "pages/missing/index"],
+  "window": { "defaultTitle": "Demo" },
+  "tabBar": { "items": [{ "pagePath": "pages/index/index", "name": "Home" }] }
+}"#;
+        let (app, style, provider) = convert("app-routes", &[
+            ("app.json", app_json),
+            ("pages/index/index.axml", "<view />"),
+            ("pages/index/index.json", r#"{ "defaultTitle": "Home", "usingComponents": {} }"#),
+            ("pages/user-center/index.axml", "<view />"),
+        ]);
+        let home = "{ path: \"/pages/index/index\", component: PagesIndexIndex, window: {\"defaultTitle\":\"Home\"} }";
+        assert!(app.contains(home));
+        assert!(app.contains("{ path: \"/pages/user-center/index\", component: PagesUserCenterIndex },"));
+        assert!(!app.contains("missing"));
+        assert!(app.contains("<Navigate to=\"/pages/index/index\" replace />"));
+        let tab_bar = "const tabBar: TabBarConfig = {\n  items: [\n    { name: \"Home\", pagePath: \"pages/index/index\" },";
+        assert!(app.contains(tab_bar));
+        assert!(app.contains("<AppLayout window={appWindow} pages={pages} tabBar={tabBar}>"));
+        assert!(!app.contains("app.css"));
+        assert!(style.is_none());
+        assert!(!app.contains("AppProvider"));
+        assert!(provider.is_none());
+    }
+
+    #[test]
+    fn global_stylesheet_is_compiled_and_imported() {
+        let (app, style, _) = convert("app-style", &[
+            ("app.json", r#"{ "pages": ["pages/index/index"] }"#),
+            ("app.less", "@import './common';\npage { padding: 75rpx; }\n"),
+            ("common.less", ".row { display: flex; }\n"),
+            ("pages/index/index.axml", "<view />"),
+        ]);
+        assert!(app.contains("import \"./app.css\";\n"));
+        assert_eq!(style.unwrap(), ".row {\n  display: flex;\n}\n\n.a-page {\n  padding: 10vw;\n}\n");
+    }
+
+    #[test]
+    fn app_script_becomes_provider() {
+        let script = "App({\n  globalData: { user: null },\n  launched: false,\n  \
+            onLaunch(options) { this.launched = true; },\n  login(name) { this.globalData.user = name; },\n});\n";
+        let (app, _, provider) = convert("app-provider", &[
+            ("app.json", r#"{ "pages": ["pages/index/index"] }"#),
+            ("app.js", script),
+            ("pages/index/index.axml", "<view />"),
+        ]);
+        assert!(app.contains("import AppProvider from \"./AppProvider\";\n"));
+        assert!(app.contains("    <AppProvider>\n      <HashRouter>\n"));
+        let provider = provider.unwrap();
+        assert!(provider.contains("import { AppContext, registerApp, useAppData, useLaunch } from \"./runtime\";\n"));
+        assert!(provider.contains("  const globalData = useAppData<any>({ user: null });\n"));
+        assert!(provider.contains("  const launched = useRef<any>(false);\n"));
+        assert!(provider.contains("  function onLaunch(options) { launched.current = true; }\n"));
+        assert!(provider.contains("  function login(name) { globalData.user = name; }\n"));
+        let instance = "  useLaunch(onLaunch);\n  const app = {\n    globalData,\n    \
+            get launched() { return launched.current; },\n    \
+            set launched(value: any) { launched.current = value; },\n    login,\n  };\n  registerApp(app);\n";
+        assert!(provider.contains(instance), "{}", provider);
+    }
+}
@@ -5,7 +5,6 @@ use std::collections::HashMap;
 pub struct FileNode {
     name: String,
     is_file: bool,
-    path: String,
     children: HashMap<String, FileNode>,
 }
 
@@ -13,11 +12,10 @@ pub struct FileNode {
 /// 定义遍历文件树方法
 /// 生成目标文件
 impl FileNode {
-    fn new(name: &str, is_file: bool, path: &str) -> Self {
+    fn new(name: &str, is_file: bool) -> Self {
         FileNode {
             name: name.to_string(),
             is_file,
-            path: path.to_string(),
             children: HashMap::new(),
         }
     }
@@ -68,22 +66,19 @@ pub mod utils {
     pub fn build_file_tree(path: &str) -> FileNode {
         let path_buf = PathBuf::from(path);
         let name = path_buf.file_name().unwrap().to_str().unwrap();
-        let mut root = FileNode::new(name, false, path);
+        let mut root = FileNode::new(name, false);
 
         if path_buf.is_dir() {
             if let Ok(entries) = std::fs::read_dir(path) {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        let entry_path = entry.path();
-                        let entry_name = entry_path.file_name().unwrap().to_str().unwrap();
-                        if entry_path.is_dir() {
-                            let child = build_file_tree(entry_path.to_str().unwrap());
-                            root.add_child(child);
-                        } else {
-                            let child =
-                                FileNode::new(entry_name, true, entry_path.to_str().unwrap());
-                            root.add_child(child);
-                        }
+                for entry in entries.flatten() {
+                    let entry_path = entry.path();
+                    let entry_name = entry_path.file_name().unwrap().to_str().unwrap();
+                    if entry_path.is_dir() {
+                        let child = build_file_tree(entry_path.to_str().unwrap());
+                        root.add_child(child);
+                    } else {
+                        let child = FileNode::new(entry_name, true);
+                        root.add_child(child);
                     }
                 }
             }
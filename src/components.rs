@@ -0,0 +1,147 @@
+//! 支付宝小程序内置组件到 React 的映射表
+
+/// 内置组件的转换目标
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    /// 直接映射为 DOM 元素
+    Dom(&'static str),
+    /// 映射为运行时组件库中的同名组件
+    Runtime(&'static str),
+}
+
+#[derive(Debug)]
+pub struct BuiltinComponent {
+    pub name: &'static str,
+    pub target: Target,
+    /// 属性映射，目标为空字符串表示该属性在 React 中没有对应，直接丢弃
+    pub props: &'static [(&'static str, &'static str)],
+}
+
+impl BuiltinComponent {
+    pub fn jsx_tag(&self) -> &'static str {
+        match self.target {
+            Target::Dom(tag) | Target::Runtime(tag) => tag,
+        }
+    }
+
+    pub fn is_runtime(&self) -> bool {
+        matches!(self.target, Target::Runtime(_))
+    }
+
+    /// 样式中对应的选择器，运行时组件的根节点带有 `a-` 加组件名的类名
+    pub fn css_selector(&self) -> String {
+        match self.target {
+            Target::Dom(tag) => tag.to_string(),
+            Target::Runtime(_) => format!(".a-{}", self.name),
+        }
+    }
+
+    /// 查找属性映射，`None` 表示原样保留
+    pub fn map_prop(&self, name: &str) -> Option<&'static str> {
+        self.props.iter().find(|(from, _)| *from == name).map(|(_, to)| *to)
+    }
+}
+
+pub const BUILTIN_COMPONENTS: &[BuiltinComponent] = &[
+    // 视图容器
+    BuiltinComponent {
+        name: "view",
+        target: Target::Dom("div"),
+        props: &[
+            ("hover-class", ""),
+            ("hover-start-time", ""),
+            ("hover-stay-time", ""),
+            ("hover-stop-propagation", ""),
+            ("disable-scroll", ""),
+            ("animation", ""),
+        ],
+    },
+    BuiltinComponent { name: "cover-view", target: Target::Dom("div"), props: &[] },
+    BuiltinComponent { name: "scroll-view", target: Target::Runtime("ScrollView"), props: &[] },
+    BuiltinComponent { name: "swiper", target: Target::Runtime("Swiper"), props: &[] },
+    BuiltinComponent { name: "swiper-item", target: Target::Runtime("SwiperItem"), props: &[] },
+    // 基础内容
+    BuiltinComponent {
+        name: "text",
+        target: Target::Dom("span"),
+        props: &[("selectable", ""), ("space", ""), ("decode", ""), ("number-of-lines", "")],
+    },
+    BuiltinComponent { name: "icon", target: Target::Runtime("Icon"), props: &[] },
+    BuiltinComponent { name: "progress", target: Target::Runtime("Progress"), props: &[] },
+    BuiltinComponent { name: "rich-text", target: Target::Runtime("RichText"), props: &[] },
+    // 表单组件
+    BuiltinComponent { name: "button", target: Target::Runtime("Button"), props: &[] },
+    BuiltinComponent { name: "form", target: Target::Runtime("Form"), props: &[] },
+    BuiltinComponent { name: "input", target: Target::Runtime("Input"), props: &[] },
+    BuiltinComponent { name: "textarea", target: Target::Runtime("Textarea"), props: &[] },
+    BuiltinComponent { name: "label", target: Target::Dom("label"), props: &[("for", "htmlFor")] },
+    BuiltinComponent { name: "checkbox-group", target: Target::Runtime("CheckboxGroup"), props: &[] },
+    BuiltinComponent { name: "checkbox", target: Target::Runtime("Checkbox"), props: &[] },
+    BuiltinComponent { name: "radio-group", target: Target::Runtime("RadioGroup"), props: &[] },
+    BuiltinComponent { name: "radio", target: Target::Runtime("Radio"), props: &[] },
+    BuiltinComponent { name: "switch", target: Target::Runtime("Switch"), props: &[] },
+    BuiltinComponent { name: "slider", target: Target::Runtime("Slider"), props: &[] },
+    BuiltinComponent { name: "picker", target: Target::Runtime("Picker"), props: &[] },
+    // 导航
+    BuiltinComponent { name: "navigator", target: Target::Runtime("Navigator"), props: &[] },
+    // 媒体组件
+    BuiltinComponent { name: "image", target: Target::Runtime("Image"), props: &[] },
+    BuiltinComponent { name: "cover-image", target: Target::Dom("img"), props: &[] },
+    BuiltinComponent {
+        name: "video",
+        target: Target::Dom("video"),
+        props: &[
+            ("autoplay", "autoPlay"),
+            ("object-fit", ""),
+            ("initial-time", ""),
+            ("show-fullscreen-btn", ""),
+            ("show-play-btn", ""),
+            ("show-center-play-btn", ""),
+            ("enable-progress-gesture", ""),
+        ],
+    },
+    // 画布与网页容器
+    BuiltinComponent { name: "canvas", target: Target::Dom("canvas"), props: &[("disable-scroll", "")] },
+    BuiltinComponent { name: "web-view", target: Target::Dom("iframe"), props: &[] },
+];
+
+pub fn find_builtin(tag: &str) -> Option<&'static BuiltinComponent> {
+    // html5ever 会把 <image> 解析成 <img>
+    let tag = if tag == "img" { "image" } else { tag };
+    BUILTIN_COMPONENTS.iter().find(|component| component.name == tag)
+}
+
+/// 样式选择器中的标签名只按组件名精确查找，`img` 等 HTML 标签保持不变
+pub fn builtin_selector(tag: &str) -> Option<String> {
+    BUILTIN_COMPONENTS.iter().find(|component| component.name == tag).map(BuiltinComponent::css_selector)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtins_map_to_dom_or_runtime_components() {
+        let view = find_builtin("view").unwrap();
+        assert_eq!((view.jsx_tag(), view.is_runtime()), ("div", false));
+        let scroll_view = find_builtin("scroll-view").unwrap();
+        assert_eq!((scroll_view.jsx_tag(), scroll_view.is_runtime()), ("ScrollView", true));
+        // html5ever 解析出的 <img> 仍按 image 查找
+        assert_eq!(find_builtin("img").unwrap().jsx_tag(), "Image");
+        assert!(find_builtin("my-list").is_none());
+    }
+
+    #[test]
+    fn props_are_renamed_dropped_or_kept() {
+        assert_eq!(find_builtin("label").unwrap().map_prop("for"), Some("htmlFor"));
+        assert_eq!(find_builtin("view").unwrap().map_prop("hover-class"), Some(""));
+        assert_eq!(find_builtin("view").unwrap().map_prop("class"), None);
+    }
+
+    #[test]
+    fn selectors_use_dom_tag_or_runtime_class() {
+        assert_eq!(builtin_selector("view").as_deref(), Some("div"));
+        assert_eq!(builtin_selector("swiper-item").as_deref(), Some(".a-swiper-item"));
+        assert_eq!(builtin_selector("img"), None);
+    }
+}
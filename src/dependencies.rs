@@ -1,7 +1,6 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::fs;
-use walkdir::WalkDir;
 use regex::Regex;
 use anyhow::{Result, Context};
 
@@ -18,7 +17,7 @@ fn resolve_path(current: &Path, import: &str) -> Option<PathBuf> {
     }
 
     let base = current.parent()?;
-    let mut full = base.join(import);
+    let full = base.join(import);
 
     if full.is_file() {
         return Some(full);
@@ -0,0 +1,168 @@
+//! 小程序事件绑定到 React 事件属性的映射
+
+/// 事件绑定所在元素的类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventTarget {
+    /// 映射为 DOM 元素的内置组件
+    Dom,
+    /// 运行时组件库中的内置组件
+    Runtime,
+    /// usingComponents 中的自定义组件
+    Component,
+}
+
+struct EventEntry {
+    /// 小写的小程序事件名
+    name: &'static str,
+    /// 还原大小写后的事件名，用于拼接 `on` 前缀
+    canonical: &'static str,
+    /// 对应的 React DOM 事件属性
+    dom: Option<&'static str>,
+}
+
+const EVENTS: &[EventEntry] = &[
+    // 触摸与手势，长按在移动端浏览器上会触发 contextmenu
+    EventEntry { name: "tap", canonical: "Tap", dom: Some("onClick") },
+    EventEntry { name: "longtap", canonical: "LongTap", dom: Some("onContextMenu") },
+    EventEntry { name: "touchstart", canonical: "TouchStart", dom: Some("onTouchStart") },
+    EventEntry { name: "touchmove", canonical: "TouchMove", dom: Some("onTouchMove") },
+    EventEntry { name: "touchend", canonical: "TouchEnd", dom: Some("onTouchEnd") },
+    EventEntry { name: "touchcancel", canonical: "TouchCancel", dom: Some("onTouchCancel") },
+    // 表单
+    EventEntry { name: "input", canonical: "Input", dom: Some("onInput") },
+    EventEntry { name: "change", canonical: "Change", dom: Some("onChange") },
+    EventEntry { name: "changing", canonical: "Changing", dom: None },
+    EventEntry { name: "confirm", canonical: "Confirm", dom: None },
+    EventEntry { name: "focus", canonical: "Focus", dom: Some("onFocus") },
+    EventEntry { name: "blur", canonical: "Blur", dom: Some("onBlur") },
+    EventEntry { name: "submit", canonical: "Submit", dom: Some("onSubmit") },
+    EventEntry { name: "reset", canonical: "Reset", dom: Some("onReset") },
+    // 滚动
+    EventEntry { name: "scroll", canonical: "Scroll", dom: Some("onScroll") },
+    EventEntry { name: "scrolltoupper", canonical: "ScrollToUpper", dom: None },
+    EventEntry { name: "scrolltolower", canonical: "ScrollToLower", dom: None },
+    // 媒体
+    EventEntry { name: "load", canonical: "Load", dom: Some("onLoad") },
+    EventEntry { name: "error", canonical: "Error", dom: Some("onError") },
+    EventEntry { name: "play", canonical: "Play", dom: Some("onPlay") },
+    EventEntry { name: "pause", canonical: "Pause", dom: Some("onPause") },
+    EventEntry { name: "ended", canonical: "Ended", dom: Some("onEnded") },
+    EventEntry { name: "timeupdate", canonical: "TimeUpdate", dom: Some("onTimeUpdate") },
+    // 动画
+    EventEntry { name: "transitionend", canonical: "TransitionEnd", dom: Some("onTransitionEnd") },
+    EventEntry { name: "animationstart", canonical: "AnimationStart", dom: Some("onAnimationStart") },
+    EventEntry { name: "animationiteration", canonical: "AnimationIteration", dom: Some("onAnimationIteration") },
+    EventEntry { name: "animationend", canonical: "AnimationEnd", dom: Some("onAnimationEnd") },
+    // 曝光与 web-view
+    EventEntry { name: "appear", canonical: "Appear", dom: None },
+    EventEntry { name: "disappear", canonical: "Disappear", dom: None },
+    EventEntry { name: "firstappear", canonical: "FirstAppear", dom: None },
+    EventEntry { name: "message", canonical: "Message", dom: None },
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventBinding {
+    /// React 事件属性名
+    pub prop: String,
+    /// 小程序事件对象上的 `type`
+    pub event_type: String,
+    /// catch 绑定需要阻止事件冒泡
+    pub catch: bool,
+}
+
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = s.get(..prefix.len())?;
+    let rest = &s[prefix.len()..];
+    (head.eq_ignore_ascii_case(prefix) && !rest.is_empty()).then_some(rest)
+}
+
+/// 去掉 `on` / `catch` 前缀，其后必须是大写字母，`once`、`online` 等普通属性不是事件
+fn strip_event_prefix<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    strip_prefix_ignore_case(s, prefix).filter(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// 解析 `onTap` / `catchTap` / `capture-onTap` / `capture-catchTap` 形式的事件属性，
+/// 不是事件绑定时返回 `None`，DOM 元素上没有对应事件时返回 `Err`
+pub fn parse_event(attr_name: &str, target: EventTarget) -> Option<Result<EventBinding, String>> {
+    let (capture, name) = match strip_prefix_ignore_case(attr_name, "capture-") {
+        Some(rest) => (true, rest),
+        None => (false, attr_name),
+    };
+    let (catch, event) = match strip_event_prefix(name, "catch") {
+        Some(rest) => (true, rest),
+        None => (false, strip_event_prefix(name, "on")?),
+    };
+
+    let lower = event.to_ascii_lowercase();
+    let entry = EVENTS.iter().find(|entry| entry.name == lower);
+    let canonical = entry.map(|entry| entry.canonical.to_string()).unwrap_or_else(|| capitalize(event));
+    // 手势事件由运行时组件透传到根 DOM 元素，其余事件由组件自行触发
+    let is_gesture = lower == "tap" || lower == "longtap" || lower.starts_with("touch");
+
+    let prop = match (target, entry.and_then(|entry| entry.dom)) {
+        (EventTarget::Dom, Some(dom)) => dom.to_string(),
+        (EventTarget::Dom, None) => return Some(Err(format!("`{}` event has no DOM equivalent", event))),
+        (EventTarget::Runtime, Some(dom)) if is_gesture => dom.to_string(),
+        _ => format!("on{}", canonical),
+    };
+    let prop = if capture { format!("{}Capture", prop) } else { prop };
+    // 自定义组件的事件只是普通的函数 props，没有冒泡可以阻止
+    let catch = catch && target != EventTarget::Component;
+    let mut chars = canonical.chars();
+    let event_type = chars.next().map(|first| first.to_ascii_lowercase().to_string() + chars.as_str()).unwrap_or_default();
+    Some(Ok(EventBinding { prop, event_type, catch }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(attr_name: &str, target: EventTarget) -> Option<(String, String, bool)> {
+        parse_event(attr_name, target).map(|result| {
+            let binding = result.unwrap();
+            (binding.prop, binding.event_type, binding.catch)
+        })
+    }
+
+    #[test]
+    fn maps_event_attributes() {
+        let cases = [
+            ("onTap", EventTarget::Dom, Some(("onClick", "tap", false))),
+            ("catchTap", EventTarget::Dom, Some(("onClick", "tap", true))),
+            ("capture-onTap", EventTarget::Dom, Some(("onClickCapture", "tap", false))),
+            ("capture-catchTouchStart", EventTarget::Dom, Some(("onTouchStartCapture", "touchStart", true))),
+            ("onLongTap", EventTarget::Dom, Some(("onContextMenu", "longTap", false))),
+            ("onTap", EventTarget::Runtime, Some(("onClick", "tap", false))),
+            ("onChange", EventTarget::Runtime, Some(("onChange", "change", false))),
+            ("onScrollToLower", EventTarget::Runtime, Some(("onScrollToLower", "scrollToLower", false))),
+            ("catchTap", EventTarget::Component, Some(("onTap", "tap", false))),
+            ("onItemClick", EventTarget::Component, Some(("onItemClick", "itemClick", false))),
+        ];
+        for (attr_name, target, expected) in cases {
+            let expected = expected.map(|(prop, event_type, catch)| (prop.to_string(), event_type.to_string(), catch));
+            assert_eq!(binding(attr_name, target), expected, "{}", attr_name);
+        }
+    }
+
+    #[test]
+    fn ordinary_attributes_are_not_events() {
+        for attr_name in ["once", "onlyOne", "online", "onion", "on", "catch", "catcher", "ontap", "capture-once"] {
+            assert!(parse_event(attr_name, EventTarget::Dom).is_none(), "{}", attr_name);
+            assert!(parse_event(attr_name, EventTarget::Component).is_none(), "{}", attr_name);
+        }
+    }
+
+    #[test]
+    fn dom_events_without_equivalent_are_errors() {
+        assert!(matches!(parse_event("onConfirm", EventTarget::Dom), Some(Err(_))));
+        assert!(matches!(parse_event("onConfirm", EventTarget::Runtime), Some(Ok(_))));
+    }
+}
@@ -0,0 +1,459 @@
+//! AXML 中 `{{ }}` 表达式的解析与翻译
+use std::{cell::RefCell, collections::BTreeSet};
+
+/// 文本或属性值按 `{{ }}` 切分后的片段
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Static(String),
+    Dynamic(String),
+}
+
+/// 表达式中可以直接使用的全局标识符
+const GLOBALS: [&str; 39] = [
+    "true", "false", "null", "undefined", "NaN", "Infinity", "typeof", "instanceof", "in",
+    "void", "new", "Math", "JSON", "Date", "Number", "String", "Boolean", "Array", "Object",
+    "RegExp", "Symbol", "BigInt", "Map", "Set", "WeakMap", "WeakSet", "Promise", "Error", "Intl",
+    "parseInt", "parseFloat", "isNaN", "isFinite", "encodeURI", "decodeURI", "encodeURIComponent",
+    "decodeURIComponent", "console", "globalThis",
+];
+
+/// 不能翻译成 JSX 表达式的关键字
+const UNSUPPORTED_KEYWORDS: [&str; 6] = ["function", "class", "var", "let", "const", "return"];
+
+/// 标识符作用域，记录循环变量、sjs 模块等局部名字
+#[derive(Debug, Default, Clone)]
+pub struct Scope {
+    locals: Vec<String>,
+    /// 为 true 时自由标识符是组件状态，直接按变量名访问，否则按模板数据 `data.x` 访问
+    state: bool,
+    /// 组件声明的状态名
+    declared: Vec<String>,
+    /// 访问到的未声明的标识符
+    undeclared: RefCell<BTreeSet<String>>,
+    /// 从 props 中解构出来的属性，`props.x` 直接按变量名访问
+    props: Vec<String>,
+}
+
+impl Scope {
+    pub fn push(&mut self, name: &str) {
+        self.locals.push(name.to_string());
+    }
+
+    pub fn pop(&mut self) {
+        self.locals.pop();
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.locals.iter().any(|local| local == name)
+    }
+
+    /// 之后翻译的表达式中自由标识符按组件状态处理，`names` 为已声明的状态
+    pub fn use_state(&mut self, names: &[String]) {
+        self.state = true;
+        self.declared = names.to_vec();
+    }
+
+    pub fn declare_props(&mut self, names: &[String]) {
+        self.props = names.to_vec();
+    }
+
+    /// 模板中访问了但既不是状态也不是 prop 的标识符
+    pub fn undeclared(&self) -> BTreeSet<String> {
+        self.undeclared.borrow().clone()
+    }
+
+    fn resolve(&self, ident: &str) -> String {
+        if self.contains(ident) || ident == "props" || GLOBALS.contains(&ident) {
+            ident.to_string()
+        } else if self.state {
+            if !self.declared.iter().chain(&self.props).any(|name| name == ident) {
+                self.undeclared.borrow_mut().insert(ident.to_string());
+            }
+            ident.to_string()
+        } else {
+            format!("data.{}", ident)
+        }
+    }
+}
+
+/// 按 `{{ }}` 切分字符串，表达式内部的字符串和对象字面量不会被提前截断
+pub fn parse_mustache(input: &str) -> Vec<Segment> {
+    let mut segments = vec![];
+    let mut rest = input;
+    while let Some(start) = rest.find("{{") {
+        let body = &rest[start + 2..];
+        let Some(end) = find_mustache_end(body) else {
+            break;
+        };
+        if start > 0 {
+            segments.push(Segment::Static(rest[..start].to_string()));
+        }
+        segments.push(Segment::Dynamic(body[..end].trim().to_string()));
+        rest = &body[end + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Static(rest.to_string()));
+    }
+    segments
+}
+
+fn find_mustache_end(body: &str) -> Option<usize> {
+    let bytes = body.as_bytes();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        match quote {
+            Some(q) => {
+                if c == b'\\' {
+                    i += 1;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                b'\'' | b'"' | b'`' => quote = Some(c),
+                b'{' => depth += 1,
+                b'}' if depth > 0 => depth -= 1,
+                b'}' if bytes.get(i + 1) == Some(&b'}') => return Some(i),
+                _ => {}
+            },
+        }
+        i += 1;
+    }
+    None
+}
+
+/// 将单个表达式中的自由标识符映射到 state / props，无法翻译时返回原因
+pub fn translate_expression(expr: &str, scope: &Scope) -> Result<String, String> {
+    // 无法翻译的表达式原样保留在注释中，其中的标识符不算访问
+    let undeclared = scope.undeclared();
+    let result = translate(expr, scope);
+    if result.is_err() {
+        *scope.undeclared.borrow_mut() = undeclared;
+    }
+    result
+}
+
+fn translate(expr: &str, scope: &Scope) -> Result<String, String> {
+    if expr.trim().is_empty() {
+        return Err("empty expression".to_string());
+    }
+
+    let chars: Vec<(usize, char)> = expr.char_indices().collect();
+    let mut out = String::new();
+    let mut last = 0;
+    let mut brackets = vec![];
+    // 上一个有效 token，用于区分成员访问和对象 key
+    let mut prev = ' ';
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if c == '\'' || c == '"' {
+            i += 1;
+            while i < chars.len() && chars[i].1 != c {
+                if chars[i].1 == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err("unterminated string".to_string());
+            }
+            i += 1;
+            prev = c;
+            continue;
+        }
+        if c == '`' {
+            return Err("template literals are not supported".to_string());
+        }
+
+        let next = chars.get(i + 1).map(|(_, c)| *c);
+        if c.is_ascii_digit() || (c == '.' && next.is_some_and(|n| n.is_ascii_digit())) {
+            while i < chars.len() && (chars[i].1.is_ascii_alphanumeric() || chars[i].1 == '.' || chars[i].1 == '_') {
+                i += 1;
+            }
+            prev = '0';
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' || c == '$' {
+            while i < chars.len() && (chars[i].1.is_alphanumeric() || chars[i].1 == '_' || chars[i].1 == '$') {
+                i += 1;
+            }
+            let end = chars.get(i).map(|(p, _)| *p).unwrap_or(expr.len());
+            let ident = &expr[pos..end];
+            let next = chars[i..].iter().map(|(_, c)| *c).find(|c| !c.is_whitespace());
+            let in_object_key = brackets.last() == Some(&'{') && matches!(prev, '{' | ',');
+            if prev == '.' || (in_object_key && next == Some(':')) {
+                // 成员属性或对象 key，可以是关键字
+            } else if UNSUPPORTED_KEYWORDS.contains(&ident) || ident == "this" {
+                return Err(format!("`{}` is not allowed in template expressions", ident));
+            } else if in_object_key && matches!(next, Some(',') | Some('}')) {
+                let resolved = scope.resolve(ident);
+                if resolved != ident {
+                    out.push_str(&expr[last..end]);
+                    out.push_str(&format!(": {}", resolved));
+                    last = end;
+                }
+            } else if let Some((name, next)) = declared_prop(expr, &chars, i, ident, scope) {
+                out.push_str(&expr[last..pos]);
+                out.push_str(name);
+                last = chars.get(next).map(|(p, _)| *p).unwrap_or(expr.len());
+                i = next;
+            } else {
+                let resolved = scope.resolve(ident);
+                out.push_str(&expr[last..pos]);
+                out.push_str(&resolved);
+                last = end;
+            }
+            prev = 'a';
+            continue;
+        }
+
+        if expr[pos..].starts_with("...") {
+            // 展开运算符后面是值，既不是成员属性也不是对象 key
+            prev = ' ';
+            i += 3;
+            continue;
+        }
+        match c {
+            '(' | '[' | '{' => brackets.push(c),
+            ')' | ']' | '}' => {
+                let open = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                if brackets.pop() != Some(open) {
+                    return Err("unbalanced brackets".to_string());
+                }
+            }
+            '=' if next == Some('>') => return Err("arrow functions are not supported".to_string()),
+            '=' if next != Some('=') && !matches!(prev, '=' | '!' | '<' | '>') => {
+                return Err("assignments are not supported".to_string());
+            }
+            '+' | '-' if next == Some(c) => {
+                return Err("increment and decrement are not supported".to_string());
+            }
+            ';' => return Err("statements are not supported".to_string()),
+            _ => {}
+        }
+        prev = c;
+        i += 1;
+    }
+
+    if !brackets.is_empty() {
+        return Err("unbalanced brackets".to_string());
+    }
+    out.push_str(&expr[last..]);
+    Ok(out.trim().to_string())
+}
+
+/// `props.x` 中的 x 是解构出来的 prop 时，返回属性名和其后的字符下标
+fn declared_prop<'e>(
+    expr: &'e str,
+    chars: &[(usize, char)],
+    mut i: usize,
+    ident: &str,
+    scope: &Scope,
+) -> Option<(&'e str, usize)> {
+    if ident != "props" || scope.contains("props") {
+        return None;
+    }
+    let skip_whitespace = |mut i: usize| {
+        while chars.get(i).is_some_and(|(_, c)| c.is_whitespace()) {
+            i += 1;
+        }
+        i
+    };
+    i = skip_whitespace(i);
+    if chars.get(i).map(|(_, c)| *c) != Some('.') {
+        return None;
+    }
+    i = skip_whitespace(i + 1);
+    let start = chars.get(i)?.0;
+    while chars.get(i).is_some_and(|(_, c)| c.is_alphanumeric() || *c == '_' || *c == '$') {
+        i += 1;
+    }
+    let end = chars.get(i).map(|(p, _)| *p).unwrap_or(expr.len());
+    let name = &expr[start..end];
+    scope.props.iter().any(|prop| prop == name).then_some((name, i))
+}
+
+/// 转换为 JS 表达式：纯表达式直接输出，混合内容输出模板字符串
+pub fn to_js_expression(value: &str, scope: &Scope) -> Result<String, String> {
+    let segments = parse_mustache(value);
+    match segments.as_slice() {
+        [] => Ok("\"\"".to_string()),
+        [Segment::Dynamic(expr)] => translate_expression(expr, scope),
+        _ if segments.iter().all(|s| matches!(s, Segment::Static(_))) => Ok(quote_string(value)),
+        _ => {
+            let mut literal = String::from("`");
+            for segment in &segments {
+                match segment {
+                    Segment::Static(text) => literal.push_str(&escape_template(text)),
+                    Segment::Dynamic(expr) => {
+                        literal.push_str(&format!("${{{}}}", translate_expression(expr, scope)?));
+                    }
+                }
+            }
+            literal.push('`');
+            Ok(literal)
+        }
+    }
+}
+
+/// 转换为 JSX 属性值，静态值保持字符串属性
+pub fn to_attr_value(value: &str, scope: &Scope) -> Result<String, String> {
+    if !value.contains("{{") {
+        return Ok(if value.contains('"') {
+            format!("{{{}}}", quote_string(value))
+        } else {
+            format!("\"{}\"", value)
+        });
+    }
+    Ok(format!("{{{}}}", to_js_expression(value, scope)?))
+}
+
+/// 转换为 JSX 子节点文本，静态部分中的 JSX 特殊字符会被转义
+pub fn to_jsx_text(value: &str, scope: &Scope) -> Result<String, String> {
+    let mut out = String::new();
+    for segment in parse_mustache(value) {
+        match segment {
+            Segment::Static(text) if text.contains(['{', '}', '<', '>']) => {
+                out.push_str(&format!("{{{}}}", quote_string(&text)));
+            }
+            Segment::Static(text) => out.push_str(&text),
+            Segment::Dynamic(expr) => {
+                out.push_str(&format!("{{{}}}", translate_expression(&expr, scope)?));
+            }
+        }
+    }
+    Ok(out)
+}
+
+pub fn quote_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn escape_template(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('`', "\\`").replace("${", "\\${")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component_scope() -> Scope {
+        let mut scope = Scope::default();
+        scope.use_state(&["list".to_string()]);
+        scope.declare_props(&["title".to_string()]);
+        scope
+    }
+
+    #[test]
+    fn splits_mixed_text_and_expressions() {
+        assert_eq!(
+            parse_mustache("a {{x}} b{{ y }}"),
+            [
+                Segment::Static("a ".to_string()),
+                Segment::Dynamic("x".to_string()),
+                Segment::Static(" b".to_string()),
+                Segment::Dynamic("y".to_string()),
+            ]
+        );
+        let scope = component_scope();
+        assert_eq!(
+            to_attr_value("item {{list.length > 0 ? 'on' : ''}}", &scope).unwrap(),
+            "{`item ${list.length > 0 ? 'on' : ''}`}"
+        );
+        assert_eq!(to_jsx_text("共 {{list.length}} 条", &scope).unwrap(), "共 {list.length} 条");
+        assert_eq!(to_jsx_text("{a} <b>", &scope).unwrap(), "{\"{a} <b>\"}");
+    }
+
+    #[test]
+    fn string_literals_may_contain_closing_braces() {
+        assert_eq!(
+            parse_mustache("{{'}}' + \"{{\"}}!"),
+            [Segment::Dynamic("'}}' + \"{{\"".to_string()), Segment::Static("!".to_string())]
+        );
+        assert_eq!(parse_mustache("{{ {a: 1}.a }}"), [Segment::Dynamic("{a: 1}.a".to_string())]);
+        assert_eq!(parse_mustache("{{x"), [Segment::Static("{{x".to_string())]);
+    }
+
+    #[test]
+    fn object_shorthand_and_spread_resolve_to_template_data() {
+        let scope = Scope::default();
+        assert_eq!(translate_expression("{...a, b, c: d.e}", &scope).unwrap(), "{...data.a, b: data.b, c: data.d.e}");
+
+        let mut scope = scope;
+        scope.push("item");
+        assert_eq!(translate_expression("{...item, index}", &scope).unwrap(), "{...item, index: data.index}");
+        scope.pop();
+        assert_eq!(translate_expression("item", &scope).unwrap(), "data.item");
+    }
+
+    #[test]
+    fn resolves_locals_state_and_props() {
+        let mut scope = component_scope();
+        scope.push("utils");
+        assert_eq!(
+            translate_expression("utils.format(list, props.title, title)", &scope).unwrap(),
+            "utils.format(list, title, title)"
+        );
+        let expr = "props.other + props['title']";
+        assert_eq!(translate_expression(expr, &scope).unwrap(), expr);
+        assert_eq!(translate_expression("{title, list}", &scope).unwrap(), "{title, list}");
+        assert!(scope.undeclared().is_empty());
+    }
+
+    #[test]
+    fn rejects_unsupported_syntax() {
+        let scope = component_scope();
+        assert!(translate_expression("list = []", &scope).is_err());
+        assert!(translate_expression("this.list", &scope).is_err());
+        assert!(translate_expression("`${list}`", &scope).is_err());
+        assert!(translate_expression("(list", &scope).is_err());
+        assert!(translate_expression("list == 1 && list !== 2", &scope).is_ok());
+    }
+
+    #[test]
+    fn keywords_are_allowed_as_member_names_and_object_keys() {
+        let mut scope = component_scope();
+        scope.push("item");
+        assert_eq!(translate_expression("item.class", &scope).unwrap(), "item.class");
+        assert_eq!(translate_expression("item.default.return", &scope).unwrap(), "item.default.return");
+        assert_eq!(translate_expression("{class: item, const: 1}", &scope).unwrap(), "{class: item, const: 1}");
+        assert!(translate_expression("{class}", &scope).is_err());
+    }
+
+    #[test]
+    fn undeclared_identifiers_are_reported() {
+        let scope = component_scope();
+        assert_eq!(translate_expression("tags.length + list.length", &scope).unwrap(), "tags.length + list.length");
+        assert_eq!(scope.undeclared().into_iter().collect::<Vec<_>>(), ["tags"]);
+    }
+
+    #[test]
+    fn globals_and_props_are_not_undeclared() {
+        let scope = component_scope();
+        let expr = "isNaN(title) ? decodeURIComponent(props.id) : new Set(list).size";
+        assert_eq!(translate_expression(expr, &scope).unwrap(), expr);
+        assert!(scope.undeclared().is_empty());
+    }
+
+    #[test]
+    fn untranslated_expressions_do_not_report_identifiers() {
+        let scope = component_scope();
+        assert!(translate_expression("tags.map(x => x)", &scope).is_err());
+        assert!(scope.undeclared().is_empty());
+    }
+}
@@ -1,8 +1,15 @@
 //! # mini2react
 //!
 //! A library for modeling artistic concepts.
+pub mod app;
 pub mod build_file_tree;
 pub mod mini_to_react;
+pub mod components;
 pub mod dependencies;
+pub mod events;
+pub mod expression;
 pub mod dep_tree;
 pub mod module_resolver;
+pub mod runtime;
+pub mod script;
+pub mod style;
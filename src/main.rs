@@ -1,4 +1,4 @@
-mod module_resolver;
+use mini2react::module_resolver;
 
 use std::path::Path;
 
@@ -68,10 +68,12 @@ fn main() {
     //             DependencyType::Component(p) |
     //             DependencyType::Style(p) |
     //             DependencyType::Script(p) |
+    //             DependencyType::Template(p) |
     //             DependencyType::Asset(p) => {
     //                 println!("  → {:?}", p);
     //                 copy_dependency(p, base_dir, target_dir);
     //             }
+    //             DependencyType::Include(p) => copy_include(p, base_dir, target_dir),
     //         }
     //     }
 
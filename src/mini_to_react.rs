@@ -1,8 +1,18 @@
-use std::{collections::HashSet, fs, io::Cursor, path::{Path, PathBuf}};
+use std::{collections::{BTreeSet, HashMap, HashSet}, fs, io::Cursor, path::{Component, Path, PathBuf}};
 use serde_json::Value;
 use regex::Regex;
-use html5ever::{parse_document, tendril::TendrilSink};
+use html5ever::{local_name, namespace_url, ns, parse_fragment, tendril::TendrilSink, Attribute, QualName};
 use markup5ever_rcdom::{Handle, NodeData, RcDom};
+use crate::components::find_builtin;
+use crate::events::{parse_event, EventBinding, EventTarget};
+use crate::runtime::{write_runtime, RUNTIME_DIR};
+use crate::style::{compile_style, convert_rpx, StyleOptions, Stylesheet};
+use crate::script::{
+    is_identifier, parse_mixin, parse_script_with, reindent, state_setter, Inherited, ObservedField, Script, ScriptKind,
+};
+use crate::expression::{
+    parse_mustache, quote_string, to_attr_value, to_js_expression, to_jsx_text, translate_expression, Scope, Segment,
+};
 use std::default::Default;
 use std::fs::create_dir_all;
 use std::fs::write;
@@ -12,20 +22,33 @@ pub enum DependencyType {
     Component(PathBuf),
     Style(PathBuf),
     Script(PathBuf),
+    Template(PathBuf),
+    /// 只通过 `<include>` 引用的 axml，内容内联到引用处，不单独转换
+    Include(PathBuf),
     Asset(PathBuf),
 }
 
+fn find_project_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors().skip(1).find(|dir| dir.join("app.json").exists()).map(Path::to_path_buf)
+}
+
+// usingComponents 中以 / 开头的路径相对于小程序根目录（app.json 所在目录）
+fn resolve_using_component(base: &Path, value: &str) -> Option<PathBuf> {
+    let path = match value.strip_prefix('/') {
+        Some(abs) => find_project_root(base)?.join(abs),
+        None => base.parent()?.join(value),
+    };
+    [path.with_extension("json"), path.join("index.json")].into_iter().find(|p| p.exists())
+}
+
 fn extract_json_components(json_str: &str, base: &Path) -> Vec<PathBuf> {
     let mut result = vec![];
     let parsed: Value = serde_json::from_str(json_str).unwrap_or(Value::Null);
     if let Some(components) = parsed.get("usingComponents") {
         if let Some(map) = components.as_object() {
             for (_k, v) in map.iter() {
-                if let Some(rel_path) = v.as_str() {
-                    let path = base.parent().unwrap().join(rel_path).join("index.json");
-                    if path.exists() {
-                        result.push(path);
-                    }
+                if let Some(path) = v.as_str().and_then(|rel_path| resolve_using_component(base, rel_path)) {
+                    result.push(path);
                 }
             }
         }
@@ -33,6 +56,63 @@ fn extract_json_components(json_str: &str, base: &Path) -> Vec<PathBuf> {
     result
 }
 
+fn relative_module(from_dir: &Path, to: &Path) -> String {
+    let from = from_dir.components().collect::<Vec<_>>();
+    let to = to.components().collect::<Vec<_>>();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut parts = vec!["..".to_string(); from.len() - common];
+    parts.extend(to[common..].iter().map(|c| c.as_os_str().to_string_lossy().into_owned()));
+    let module = parts.join("/");
+    if module.starts_with("..") { module } else { format!("./{}", module) }
+}
+
+// usingComponents 的值转换为生成的 tsx 中可用的 import 路径
+fn component_module(axml_path: &Path, value: &str) -> Option<String> {
+    if value.starts_with("plugin://") {
+        return None;
+    }
+    let module = value.strip_suffix("/index").unwrap_or(value);
+    match module.strip_prefix('/') {
+        Some(abs) => {
+            let root = find_project_root(axml_path)?;
+            Some(relative_module(axml_path.parent()?, &root.join(abs)))
+        }
+        None => Some(module.to_string()),
+    }
+}
+
+fn extract_using_components(axml_path: &Path) -> Vec<(String, String)> {
+    let content = fs::read_to_string(axml_path.with_extension("json")).unwrap_or_default();
+    let parsed: Value = serde_json::from_str(&content).unwrap_or(Value::Null);
+    parsed
+        .get("usingComponents")
+        .and_then(Value::as_object)
+        .map(|map| {
+            map.iter()
+                .filter_map(|(tag, v)| Some((tag.clone(), v.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// html5ever 会把属性名转成小写，这里从源码中找回原始大小写
+fn collect_attr_names(content: &str) -> HashMap<String, String> {
+    let tag_re = Regex::new(r#"<[a-zA-Z][\w:-]*((?:[^>"']|"[^"]*"|'[^']*')*)>"#).unwrap();
+    let value_re = Regex::new(r#""[^"]*"|'[^']*'"#).unwrap();
+    let name_re = Regex::new(r"[A-Za-z_:][\w:.-]*").unwrap();
+    let mut names = HashMap::new();
+    for cap in tag_re.captures_iter(content) {
+        let attrs = value_re.replace_all(&cap[1], " ");
+        for name in name_re.find_iter(&attrs) {
+            let name = name.as_str();
+            if name.chars().any(|c| c.is_ascii_uppercase()) {
+                names.insert(name.to_ascii_lowercase(), name.to_string());
+            }
+        }
+    }
+    names
+}
+
 fn extract_style_imports(content: &str, base: &Path) -> Vec<PathBuf> {
     let mut deps = vec![];
     for line in content.lines() {
@@ -52,31 +132,168 @@ fn extract_style_imports(content: &str, base: &Path) -> Vec<PathBuf> {
 }
 
 fn extract_script_imports(content: &str, base: &Path) -> Vec<PathBuf> {
-    println!("extract_script_imports {:?}", base);
     let mut result = vec![];
     let re = Regex::new(r#"(import.*from\s+|require\()\s*[\"']([^\"']+)[\"']"#).unwrap();
     for cap in re.captures_iter(content) {
-        let raw = cap[2].to_string();
-        if raw.starts_with(".") {
-            let p = base.parent().unwrap().join(&raw);
-            for ext in ["js", "ts", "json"] {
-                let candidate = p.with_extension(ext);
-                if candidate.exists() {
-                    result.push(candidate);
-                    break;
-                }
-            }
+        if let Some(dep) = resolve_script_import(&cap[2], base) {
+            result.push(dep);
         }
     }
     result
 }
 
+// 相对路径的模块依次尝试 js、ts、json 扩展名
+fn resolve_script_import(raw: &str, base: &Path) -> Option<PathBuf> {
+    if !raw.starts_with('.') {
+        return None;
+    }
+    let p = base.parent()?.join(raw);
+    ["js", "ts", "json"].into_iter().map(|ext| p.with_extension(ext)).find(|candidate| candidate.exists())
+}
+
+// 去掉路径中的 `.` 和 `..`
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+// mixin 中的相对 import 改为相对组件所在目录
+fn rebase_import(import: &str, from_dir: &Path, to_dir: &Path) -> String {
+    let re = Regex::new(r#"(from\s+|import\s+)(["'])(\.[^"']*)["']"#).unwrap();
+    re.replace(import, |cap: &regex::Captures| {
+        let module = relative_module(to_dir, &normalize_path(&from_dir.join(&cap[3])));
+        format!("{}{}{}{}", &cap[1], &cap[2], module, &cap[2])
+    })
+    .into_owned()
+}
+
+// 与 axml 同名的 acss / less
+fn component_stylesheet(axml_path: &Path) -> Option<PathBuf> {
+    ["acss", "less"].into_iter().map(|ext| axml_path.with_extension(ext)).find(|path| path.exists())
+}
+
+/// 组件样式是否输出为 CSS Modules，app 等没有模板的样式和 `styleIsolation: "shared"` 的组件样式是全局的
+fn uses_css_module(path: &Path, options: &StyleOptions) -> bool {
+    let json = fs::read_to_string(path.with_extension("json")).unwrap_or_default();
+    let json = serde_json::from_str::<Value>(&json).unwrap_or(Value::Null);
+    let shared = json.get("styleIsolation").and_then(Value::as_str) == Some("shared");
+    options.css_modules && path.with_extension("axml").exists() && !shared
+}
+
+// 同名的 acss / less 编译为 css 后由组件导入，CSS Modules 导入为 styles
+fn style_import(axml_path: &Path, css_module: bool) -> Option<String> {
+    component_stylesheet(axml_path)?;
+    let stem = axml_path.file_stem()?.to_string_lossy();
+    Some(match css_module {
+        true => format!("import styles from \"./{}.module.css\";\n", stem),
+        false => format!("import \"./{}.css\";\n", stem),
+    })
+}
+
+/// 解析组件脚本并合并其中的 mixins
+///
+/// 组件和 mixin 的方法、props 可以通过 this 互相访问，先解析一遍收集全部成员名和状态名，再带着这些名字重新解析
+fn load_script(path: &Path) -> Script {
+    let parse = |inherited: &Inherited| {
+        parse_script_with(path, inherited).unwrap_or_else(|reason| {
+            eprintln!("{:?}: {}", path, reason);
+            Script::default()
+        })
+    };
+    let script = parse(&Inherited::default());
+    let mut mixins = vec![];
+    for (name, source) in &script.mixins {
+        match resolve_script_import(source, path) {
+            Some(mixin_path) => mixins.push((name.clone(), mixin_path)),
+            None => eprintln!("{:?}: mixin not found: `{}`", path, source),
+        }
+    }
+    let mut inherited = script.inherited();
+    let mut state_names = script.state_names();
+    for (_, mixin_path) in &mixins {
+        if let Ok(mixin) = parse_mixin(mixin_path, &Inherited::default()) {
+            let members = mixin.inherited();
+            inherited.members.extend(members.members);
+            inherited.props.extend(members.props);
+            state_names.extend(mixin.state_names());
+        }
+    }
+    // 与状态同名的 prop 不能和 useState 一起解构，重新解析使 this.props.x 访问 props 对象
+    inherited.shadowed = inherited.props.iter().filter(|prop| state_names.contains(*prop)).cloned().collect();
+    if mixins.is_empty() && inherited.shadowed.is_empty() {
+        return script;
+    }
+
+    let mut script = parse(&inherited);
+    let dir = path.parent().unwrap_or(path);
+    for (name, mixin_path) in mixins.iter().rev() {
+        let mut mixin = match parse_mixin(mixin_path, &inherited) {
+            Ok(mixin) => mixin,
+            Err(reason) => {
+                eprintln!("{:?}: {}", mixin_path, reason);
+                continue;
+            }
+        };
+        for warning in &mixin.warnings {
+            eprintln!("{:?}: {}", mixin_path, warning);
+        }
+        if !mixin.mixins.is_empty() {
+            eprintln!("{:?}: nested mixins are not supported", mixin_path);
+        }
+        let mixin_dir = mixin_path.parent().unwrap_or(mixin_path);
+        mixin.imports = mixin.imports.iter().map(|import| rebase_import(import, mixin_dir, dir)).collect();
+        script.merge_mixin(name, mixin);
+    }
+    script
+}
+
+// html5ever 按 HTML 规则解析，非空元素的 `<x/>` 会被当作开始标签，先展开成 `<x></x>`
+fn expand_self_closing(content: &str) -> String {
+    let re = Regex::new(r#"<([a-zA-Z][\w:-]*)((?:[^>"']|"[^"]*"|'[^']*')*?)\s*/>"#).unwrap();
+    re.replace_all(content, "<$1$2></$1>").into_owned()
+}
+
+fn parse_axml(content: &str) -> RcDom {
+    let content = expand_self_closing(content);
+    parse_fragment(
+        RcDom::default(),
+        Default::default(),
+        QualName::new(None, ns!(html), local_name!("body")),
+        vec![],
+    )
+    .from_utf8()
+    .read_from(&mut Cursor::new(content.as_bytes()))
+    .unwrap()
+}
+
+fn attr_value(node: &Handle, attr_name: &str) -> Option<String> {
+    if let NodeData::Element { ref attrs, .. } = node.data {
+        attrs
+            .borrow()
+            .iter()
+            .find(|attr| attr.name.local.as_ref() == attr_name)
+            .map(|attr| attr.value.to_string())
+    } else {
+        None
+    }
+}
+
+const BOOLEAN_ATTRS: [&str; 7] = ["hidden", "disabled", "checked", "autoPlay", "loop", "muted", "controls"];
+
+const DIRECTIVES: [&str; 7] = ["a:if", "a:elif", "a:else", "a:for", "a:for-item", "a:for-index", "a:key"];
+
 fn extract_import_sjs_paths(axml_path: &Path) -> Vec<PathBuf> {
     let content = fs::read_to_string(axml_path).unwrap_or_default();
-    let dom = parse_document(RcDom::default(), Default::default())
-        .from_utf8()
-        .read_from(&mut content.as_bytes())
-        .unwrap();
+    let dom = parse_axml(&content);
 
     let mut result = vec![];
     fn walk(node: &Handle, base: &Path, out: &mut Vec<PathBuf>) {
@@ -101,6 +318,47 @@ fn extract_import_sjs_paths(axml_path: &Path) -> Vec<PathBuf> {
     result
 }
 
+// import / include 的 src 以 / 开头时相对于小程序根目录（app.json 所在目录）
+fn resolve_axml_src(base: &Path, src: &str) -> Option<PathBuf> {
+    let mut path = match src.strip_prefix('/') {
+        Some(abs) => find_project_root(base)?.join(abs),
+        None => normalize_path(&base.parent()?.join(src)),
+    };
+    if path.extension().is_none() {
+        path.set_extension("axml");
+    }
+    path.exists().then_some(path)
+}
+
+fn extract_template_paths(axml_path: &Path) -> Vec<DependencyType> {
+    let content = fs::read_to_string(axml_path).unwrap_or_default();
+    let dom = parse_axml(&content);
+
+    let mut result = vec![];
+    fn walk(node: &Handle, base: &Path, out: &mut Vec<DependencyType>) {
+        if let NodeData::Element { ref name, .. } = node.data {
+            let tag = name.local.as_ref();
+            if matches!(tag, "import" | "include") {
+                if let Some(path) = attr_value(node, "src").and_then(|src| resolve_axml_src(base, &src)) {
+                    out.push(match tag {
+                        "import" => DependencyType::Template(path),
+                        _ => DependencyType::Include(path),
+                    });
+                }
+            }
+        }
+        for child in node.children.borrow().iter() {
+            walk(child, base, out);
+        }
+        if let Some(contents) = template_contents(node) {
+            walk(&contents, base, out);
+        }
+    }
+
+    walk(&dom.document, axml_path, &mut result);
+    result
+}
+
 pub fn collect_all_dependencies(
     path: &Path,
     visited: &mut HashSet<PathBuf>,
@@ -117,6 +375,13 @@ pub fn collect_all_dependencies(
                     collect_all_dependencies(&dep, visited, deps);
                     deps.push(DependencyType::Component(dep));
                 }
+                // 组件自身的模板、脚本和样式中也会引用其他文件
+                for ext in ["axml", "js", "ts", "acss", "less"] {
+                    let sibling = path.with_extension(ext);
+                    if sibling.exists() {
+                        collect_all_dependencies(&sibling, visited, deps);
+                    }
+                }
             },
             "less" | "acss" => {
                 for dep in extract_style_imports(&content, path) {
@@ -131,10 +396,23 @@ pub fn collect_all_dependencies(
                 }
             },
             "axml" => {
-                for dep in extract_import_sjs_paths(&path) {
+                // 没有 json 配置的页面直接从 axml 进入，同名的脚本和样式同样需要遍历
+                for ext in ["js", "ts", "acss", "less"] {
+                    let sibling = path.with_extension(ext);
+                    if sibling.exists() {
+                        collect_all_dependencies(&sibling, visited, deps);
+                    }
+                }
+                for dep in extract_import_sjs_paths(path) {
                     collect_all_dependencies(&dep, visited, deps);
                     deps.push(DependencyType::Script(dep));
                 }
+                for dep in extract_template_paths(path) {
+                    if let DependencyType::Template(target) | DependencyType::Include(target) = &dep {
+                        collect_all_dependencies(target, visited, deps);
+                    }
+                    deps.push(dep);
+                }
             },
             _ => {}
         }
@@ -157,7 +435,38 @@ pub fn scan_component_dirs(base_dir: &Path) -> Vec<PathBuf> {
     result
 }
 
+/// app.json 中 pages 列出的页面入口，有 json 配置时返回 json，否则返回 axml
+pub fn scan_page_entries(project_root: &Path) -> Vec<PathBuf> {
+    let content = fs::read_to_string(project_root.join("app.json")).unwrap_or_default();
+    let parsed: Value = serde_json::from_str(&content).unwrap_or(Value::Null);
+    parsed
+        .get("pages")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter_map(|page| {
+            let page = project_root.join(page.trim_start_matches('/'));
+            [page.with_extension("json"), page.with_extension("axml")].into_iter().find(|p| p.exists())
+        })
+        .collect()
+}
+
+// 组件、页面的脚本合并到同名 tsx 中，app.js 转换为 AppProvider.tsx
+fn is_converted_script(path: &Path) -> bool {
+    let is_script = path.extension().is_some_and(|ext| ext == "js" || ext == "ts");
+    let is_app = path.file_stem().is_some_and(|stem| stem == "app") && path.with_file_name("app.json").exists();
+    is_script && (path.with_extension("axml").exists() || is_app)
+}
+
 pub fn copy_dependency(dep: &PathBuf, source_root: &Path, target_root: &Path) {
+    copy_dependency_with(dep, source_root, target_root, &StyleOptions::default());
+}
+
+/// 复制只被 include 的 axml，内容已内联到引用处，不生成组件
+///
+/// 同时被 import 或作为页面入口的文件仍按对应的依赖转换
+pub fn copy_include(dep: &PathBuf, source_root: &Path, target_root: &Path) {
     if let Ok(rel_path) = dep.strip_prefix(source_root) {
         let target_path = target_root.join(rel_path);
         if let Some(parent) = target_path.parent() {
@@ -165,43 +474,58 @@ pub fn copy_dependency(dep: &PathBuf, source_root: &Path, target_root: &Path) {
         }
         if let Err(e) = fs::copy(dep, &target_path) {
             eprintln!("Failed to copy {:?} to {:?}: {}", dep, target_path, e);
-        } else {
-            // println!("✔ Copied {:?} -> {:?}", dep, target_path);
+        }
+    }
+}
 
+/// 复制依赖文件，axml 转换为 tsx，acss / less 编译为同名的 css，组件样式开启 CSS Modules 时为 `.module.css`
+pub fn copy_dependency_with(dep: &PathBuf, source_root: &Path, target_root: &Path, style_options: &StyleOptions) {
+    if let Ok(rel_path) = dep.strip_prefix(source_root) {
+        let target_path = target_root.join(rel_path);
+        if let Some(parent) = target_path.parent() {
+            let _ = create_dir_all(parent);
+        }
+        // 已转换为 tsx 的脚本不再复制，否则不带扩展名的 import 会优先解析到原始的 js
+        if is_converted_script(dep) {
+            let _ = fs::remove_file(&target_path);
+            return;
+        }
+        if let Err(e) = fs::copy(dep, &target_path) {
+            eprintln!("Failed to copy {:?} to {:?}: {}", dep, target_path, e);
+        } else {
             if let Some(ext) = dep.extension().and_then(|s| s.to_str()) {
                 if ext == "axml" {
-                    let jsx = convert_axml_to_jsx(dep);
+                    if let Err(e) = write_runtime(target_root) {
+                        eprintln!("Failed to write runtime to {:?}: {}", target_root, e);
+                    }
+                    let runtime_module = relative_module(
+                        target_path.parent().unwrap_or(target_root),
+                        &target_root.join(RUNTIME_DIR),
+                    );
+                    let jsx = convert_axml_to_jsx(dep, &runtime_module, style_options);
                     let mut jsx_path = target_path.clone();
                     jsx_path.set_extension("tsx");
                     let _ = write(&jsx_path, jsx);
-                    // println!("✔ Converted AXML to JSX: {:?}", jsx_path);
+                }
+                if ext == "acss" || ext == "less" {
+                    let stylesheet = compile_style(dep, style_options);
+                    for warning in &stylesheet.warnings {
+                        eprintln!("{:?}: {}", dep, warning);
+                    }
+                    let css_path = match uses_css_module(dep, style_options) {
+                        true => target_path.with_extension("module.css"),
+                        false => target_path.with_extension("css"),
+                    };
+                    if let Err(e) = write(&css_path, stylesheet.to_css()) {
+                        eprintln!("Failed to write {:?}: {}", css_path, e);
+                    }
                 }
             }
         }
     }
 }
 
-fn extract_methods_from_script(script_path: &Path) -> Vec<String> {
-    let content = fs::read_to_string(script_path).unwrap_or_default();
-    let mut methods = vec![];
-    let re = Regex::new(r#"methods\s*:\s*\{\s*((.|\n)*?)\s*\}"#).unwrap();
-
-    if let Some(cap) = re.captures(&content) {
-        let body = &cap[1];
-        let func_re = Regex::new(r#"(?m)^\s*(\w+)\s*\((.*?)\)\s*\{\s*((.|\n)*?)\n\s*\}"#).unwrap();
-        for cap in func_re.captures_iter(body) {
-            let name = &cap[1];
-            let args = &cap[2];
-            let body = &cap[3];
-            let func = format!("function {}({}) {{\n{}\n}}", name, args, body.trim());
-            methods.push(func);
-        }
-    }
-
-    methods
-}
-
-fn to_camel_case(s: &str) -> String {
+pub(crate) fn to_camel_case(s: &str) -> String {
     let mut result = String::new();
     let mut uppercase_next = true;
     for c in s.chars() {
@@ -217,96 +541,1044 @@ fn to_camel_case(s: &str) -> String {
     result
 }
 
-fn convert_axml_to_jsx(axml_path: &Path) -> String {
+struct JsxContext {
+    events: HashSet<String>,
+    scope: Scope,
+    warnings: Vec<String>,
+    // 是否存在 `<template is="{{expr}}">` 动态模板
+    dynamic_templates: bool,
+    // 当前正在转换的文件，include 时入栈
+    files: Vec<PathBuf>,
+    // 是否存在带参数的作用域插槽
+    scoped_slots: bool,
+    // usingComponents 中的标签（小写）到导入组件名的映射
+    components: HashMap<String, String>,
+    // 小写属性名到源码中原始属性名的映射
+    attr_names: HashMap<String, String>,
+    // 用到的运行时组件
+    runtime_imports: BTreeSet<&'static str>,
+    // CSS Modules 中组件样式声明的类名，未开启时为 None
+    module_classes: Option<BTreeSet<String>>,
+    // externalClasses 中的类名到对应 prop 的映射
+    external_classes: Vec<(String, String)>,
+    // 行内样式中 rpx 的换算方式
+    style_options: StyleOptions,
+    // 是否正在转换具名模板，模板中的事件回调从 data 参数读取
+    in_template: bool,
+    // 模板名到模板中事件回调名的映射，使用模板时传入同名方法
+    template_handlers: HashMap<String, BTreeSet<String>>,
+}
+
+impl JsxContext {
+    fn warn(&mut self, value: &str, reason: &str) {
+        self.warnings.push(format!("{}: `{}`", reason, value));
+    }
+
+    // 翻译失败时记录警告，并用 fallback 占位
+    fn expression(&mut self, value: &str, fallback: &str) -> String {
+        match to_js_expression(value, &self.scope) {
+            Ok(expr) => expr,
+            Err(reason) => {
+                self.warn(value, &reason);
+                format!("{} {}", fallback, untranslated_comment(value))
+            }
+        }
+    }
+}
+
+fn to_lower_camel_case(s: &str) -> String {
+    let camel = to_camel_case(s);
+    let mut chars = camel.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => camel,
+    }
+}
+
+fn slot_prop_name(name: &str) -> String {
+    to_lower_camel_case(name)
+}
+
+fn template_component_name(name: &str) -> String {
+    format!("{}Template", to_camel_case(name))
+}
+
+fn template_contents(node: &Handle) -> Option<Handle> {
+    match &node.data {
+        NodeData::Element { template_contents: Some(contents), .. } => Some(contents.clone()),
+        _ => None,
+    }
+}
+
+fn untranslated_comment(value: &str) -> String {
+    format!("/* untranslated: {} */", value.replace("*/", "* /"))
+}
+
+/// 页面生命周期与运行时 hook 的对应关系
+const PAGE_HOOKS: [(&str, &str); 8] = [
+    ("onLoad", "useLoad"),
+    ("onShow", "useShow"),
+    ("onReady", "useReady"),
+    ("onHide", "useHide"),
+    ("onUnload", "useUnload"),
+    ("onPageScroll", "usePageScroll"),
+    ("onReachBottom", "useReachBottom"),
+    ("onPullDownRefresh", "usePullDownRefresh"),
+];
+
+// 按不在 {{ }}、引号和括号中的分隔符切分行内样式，once 为 true 时只切分第一处
+fn split_style(style: &str, separator: char, once: bool) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut quote = None;
+    let mut parens = 0usize;
+    for segment in parse_mustache(style) {
+        let text = match segment {
+            Segment::Static(text) => text,
+            Segment::Dynamic(expr) => {
+                parts.last_mut().unwrap().push_str(&format!("{{{{{}}}}}", expr));
+                continue;
+            }
+        };
+        for c in text.chars() {
+            match c {
+                _ if quote == Some(c) => quote = None,
+                _ if quote.is_some() => {}
+                '"' | '\'' => quote = Some(c),
+                '(' => parens += 1,
+                ')' => parens = parens.saturating_sub(1),
+                c if c == separator && parens == 0 && !(once && parts.len() > 1) => {
+                    parts.push(String::new());
+                    continue;
+                }
+                _ => {}
+            }
+            parts.last_mut().unwrap().push(c);
+        }
+    }
+    parts
+}
+
+/// css 属性名对应的 React 样式对象键名：`-webkit-` 前缀为 `Webkit`，`-ms-` 前缀为 `ms`，自定义属性保持原样
+fn style_property(property: &str) -> String {
+    let property = property.trim();
+    if property.starts_with("--") {
+        return quote_string(property);
+    }
+    let property = property.to_ascii_lowercase();
+    match property.strip_prefix("-ms-") {
+        Some(rest) => format!("ms{}", to_camel_case(rest)),
+        None if property.starts_with('-') => to_camel_case(&property),
+        None => to_lower_camel_case(&property),
+    }
+}
+
+/// 字符串、数字和布尔字面量，useState 可以直接推断类型
+fn is_primitive_literal(value: &str) -> bool {
+    let value = value.trim();
+    let quoted = value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\'')));
+    (quoted && !value.contains('\n')) || value == "true" || value == "false" || value.parse::<f64>().is_ok()
+}
+
+fn convert_axml_to_jsx(axml_path: &Path, runtime_module: &str, style_options: &StyleOptions) -> String {
     let axml_content = fs::read_to_string(axml_path).unwrap_or_default();
-    let dom = parse_document(RcDom::default(), Default::default())
-        .from_utf8()
-        .read_from(&mut Cursor::new(axml_content.as_bytes()))
-        .unwrap();
+    let dom = parse_axml(&axml_content);
+    // 样式编译的警告在复制样式文件时输出，这里只需要其中的类名
+    let css_module = component_stylesheet(axml_path)
+        .filter(|path| uses_css_module(path, style_options))
+        .map(|path| compile_style(&path, style_options));
 
-    let mut events = HashSet::new();
+    let mut ctx = JsxContext {
+        events: HashSet::new(),
+        scope: Scope::default(),
+        warnings: vec![],
+        dynamic_templates: false,
+        files: vec![axml_path.to_path_buf()],
+        scoped_slots: false,
+        components: HashMap::new(),
+        attr_names: collect_attr_names(&axml_content),
+        runtime_imports: BTreeSet::new(),
+        module_classes: css_module.as_ref().map(Stylesheet::class_names),
+        external_classes: vec![],
+        style_options: style_options.clone(),
+        in_template: false,
+        template_handlers: HashMap::new(),
+    };
 
-    fn convert_style(style: &str) -> String {
-        let mut result = String::from("{{");
-        for part in style.split(';') {
-            if let Some((key, value)) = part.split_once(':') {
-                let key = key.trim().replace("-", "");
-                let value = value.trim();
-                if !key.is_empty() && !value.is_empty() {
-                    result.push_str(&format!(r#"{}: \"{}\", "#, key, value));
+    // 行内样式转换为 React 样式对象，属性名或整条声明是表达式时由运行时 styleObject 解析
+    fn convert_style(style: &str, ctx: &mut JsxContext) -> String {
+        let mut fields = vec![];
+        for declaration in split_style(style, ';', false) {
+            if declaration.trim().is_empty() {
+                continue;
+            }
+            let parts = split_style(&declaration, ':', true);
+            let [property, value] = parts.as_slice() else {
+                if !declaration.contains("{{") {
+                    ctx.warn(declaration.trim(), "invalid style declaration");
+                    continue;
                 }
+                return runtime_style(style, ctx);
+            };
+            if property.contains("{{") {
+                return runtime_style(style, ctx);
             }
+            let value = value.trim();
+            let value = match value.strip_suffix("!important") {
+                Some(value) => {
+                    ctx.warn(declaration.trim(), "!important is not supported in inline styles");
+                    value.trim_end()
+                }
+                None => value,
+            };
+            if property.trim().is_empty() || value.is_empty() {
+                continue;
+            }
+            fields.push(format!("{}: {}", style_property(property), style_value(value, ctx)));
+        }
+        if fields.is_empty() {
+            return "{{}}".to_string();
+        }
+        // CSSProperties 类型中没有自定义属性
+        match fields.iter().any(|field| field.starts_with("\"--")) {
+            true => format!("{{{{ {} }} as React.CSSProperties}}", fields.join(", ")),
+            false => format!("{{{{ {} }}}}", fields.join(", ")),
+        }
+    }
+
+    // 表达式后的 rpx 换算为 calc，静态部分的 rpx 直接换算
+    fn style_value(value: &str, ctx: &mut JsxContext) -> String {
+        let unit = convert_rpx("1rpx", &ctx.style_options);
+        let value = Regex::new(r"(\{\{.*?\}\})rpx\b").unwrap().replace_all(value, format!("calc($1 * {})", unit));
+        let value = convert_rpx(&value, &ctx.style_options);
+        match value.contains("{{") {
+            true => ctx.expression(&value, "undefined"),
+            false => quote_string(&value),
         }
-        result.push_str("}}");
-        result
     }
 
-    fn convert_attr(name: &str, value: &str, events: &mut HashSet<String>) -> Option<(String, String)> {
-        match name {
-            "class" => Some(("className".to_string(), format!(r#"\"{}\""#, value))),
-            "style" => Some(("style".to_string(), convert_style(value))),
-            s if s.starts_with("on") => {
-                let event = match &s[2..] {
-                    "tap" => "onClick",
-                    other => &format!("on{}", other),
+    fn runtime_style(style: &str, ctx: &mut JsxContext) -> String {
+        let unit = convert_rpx("1rpx", &ctx.style_options);
+        ctx.runtime_imports.insert("styleObject");
+        format!("{{styleObject({}, {})}}", ctx.expression(style, "\"\""), quote_string(&unit))
+    }
+
+    // CSS Modules 中组件样式声明的类名读取 styles，externalClasses 读取对应的 prop，其余仍是全局类名
+    fn module_class_name(value: &str, ctx: &mut JsxContext) -> Option<String> {
+        if value.contains("{{") {
+            let expr = class_builder(value, ctx);
+            let external = ctx
+                .external_classes
+                .iter()
+                .map(|(class, prop)| format!("{}: {}", quote_string(class), prop))
+                .collect::<Vec<_>>();
+            let external = if external.is_empty() { String::new() } else { format!(", {{ {} }}", external.join(", ")) };
+            ctx.runtime_imports.insert("scopedClass");
+            return Some(format!("{{scopedClass(styles, {}{})}}", expr, external));
+        }
+        let module_classes = ctx.module_classes.as_ref()?;
+        // 每个类名对应的表达式，以及是否是可能为空的 prop
+        let classes = value
+            .split_whitespace()
+            .map(|class| match ctx.external_classes.iter().find(|(name, _)| name == class) {
+                Some((_, prop)) => (class, Some((prop.clone(), true))),
+                None if module_classes.contains(class) => {
+                    let expr = match is_identifier(class) {
+                        true => format!("styles.{}", class),
+                        false => format!("styles[{}]", quote_string(class)),
+                    };
+                    (class, Some((expr, false)))
+                }
+                None => (class, None),
+            })
+            .collect::<Vec<_>>();
+        match classes.as_slice() {
+            _ if classes.iter().all(|(_, expr)| expr.is_none()) => None,
+            [(_, Some((expr, _)))] => Some(format!("{{{}}}", expr)),
+            _ => {
+                let parts = classes
+                    .iter()
+                    .map(|(class, expr)| match expr {
+                        Some((prop, true)) => format!("${{{} ?? \"\"}}", prop),
+                        Some((expr, false)) => format!("${{{}}}", expr),
+                        None => class.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                Some(format!("{{`{}`}}", parts.join(" ")))
+            }
+        }
+    }
+
+    // 按空白切分类名，单独的表达式作为 cx 的参数，与静态文本相连的表达式组成模板字符串
+    fn class_builder(value: &str, ctx: &mut JsxContext) -> String {
+        let mut words = vec![String::new()];
+        for segment in parse_mustache(value) {
+            match segment {
+                Segment::Dynamic(expr) => words.last_mut().unwrap().push_str(&format!("{{{{{}}}}}", expr)),
+                Segment::Static(text) => {
+                    for (i, piece) in text.split(char::is_whitespace).enumerate() {
+                        if i > 0 {
+                            words.push(String::new());
+                        }
+                        words.last_mut().unwrap().push_str(piece);
+                    }
+                }
+            }
+        }
+        // 相邻的静态类名合并为一个字符串参数
+        let mut args = vec![];
+        let mut statics = vec![];
+        for word in words.iter().filter(|word| !word.is_empty()) {
+            if !word.contains("{{") {
+                statics.push(word.as_str());
+                continue;
+            }
+            if !statics.is_empty() {
+                args.push(quote_string(&statics.join(" ")));
+                statics.clear();
+            }
+            args.push(ctx.expression(word, "undefined"));
+        }
+        if !statics.is_empty() {
+            args.push(quote_string(&statics.join(" ")));
+        }
+        ctx.runtime_imports.insert("cx");
+        format!("cx({})", args.join(", "))
+    }
+
+    // 带表达式的类名由 cx 拼接，静态类名合并多余的空白
+    fn class_name(value: &str, ctx: &mut JsxContext) -> String {
+        if ctx.module_classes.is_some() {
+            if let Some(value) = module_class_name(value, ctx) {
+                return value;
+            }
+        }
+        if value.contains("{{") {
+            return format!("{{{}}}", class_builder(value, ctx));
+        }
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        to_attr_value(&value, &ctx.scope).unwrap_or_default()
+    }
+
+    fn convert_attr(name: &str, value: &str, ctx: &mut JsxContext) -> Option<(String, String)> {
+        let name = match name {
+            "class" => return Some(("className".to_string(), class_name(value, ctx))),
+            "style" => return Some(("style".to_string(), convert_style(value, ctx))),
+            other => other,
+        };
+        match to_attr_value(value, &ctx.scope) {
+            Ok(value) => Some((name.to_string(), value)),
+            Err(reason) => {
+                ctx.warn(value, &reason);
+                None
+            }
+        }
+    }
+
+    // 事件属性值是组件方法名，内置组件上的回调由 bindEvent 包装为小程序事件对象
+    fn event_value(value: &str, ctx: &mut JsxContext) -> Option<String> {
+        if value.contains("{{") {
+            Some(ctx.expression(value, "undefined"))
+        } else if ctx.in_template && !value.trim().is_empty() {
+            Some(format!("data.{}", value.trim()))
+        } else if !value.trim().is_empty() {
+            ctx.events.insert(value.trim().to_string());
+            Some(value.trim().to_string())
+        } else {
+            None
+        }
+    }
+
+    // 同一元素上对应同一个 React 事件的绑定（如 onTap 和 catchTap）合并为一个回调，按声明顺序依次调用
+    fn event_handler(
+        binding: &EventBinding,
+        handlers: &[String],
+        target: EventTarget,
+        dataset: &str,
+        ctx: &mut JsxContext,
+    ) -> String {
+        // 自定义组件的事件回调由组件直接调用，参数原样传递
+        if target == EventTarget::Component {
+            return match handlers {
+                [handler] => handler.clone(),
+                _ => {
+                    let calls = handlers.iter().map(|handler| format!("{}(...args);", handler)).collect::<Vec<_>>();
+                    format!("(...args: any[]) => {{ {} }}", calls.join(" "))
+                }
+            };
+        }
+        let handler = match handlers {
+            [handler] => handler.clone(),
+            _ => format!("[{}]", handlers.join(", ")),
+        };
+        ctx.runtime_imports.insert("bindEvent");
+        let mut options = vec![];
+        if dataset != "{}" {
+            options.push(format!("dataset: {}", dataset));
+        }
+        if binding.catch {
+            options.push("catch: true".to_string());
+        }
+        let options = if options.is_empty() {
+            String::new()
+        } else {
+            format!(", {{ {} }}", options.join(", "))
+        };
+        format!("bindEvent({}, {}{})", handler, quote_string(&binding.event_type), options)
+    }
+
+    // 元素上的 data-* 属性，key 按小程序规则转为驼峰
+    fn element_dataset(attrs: &[Attribute], ctx: &mut JsxContext) -> String {
+        let entries = attrs
+            .iter()
+            .filter_map(|attr| {
+                let name = attr.name.local.as_ref().strip_prefix("data-")?;
+                let key = to_lower_camel_case(name);
+                let value = ctx.expression(&attr.value, "undefined");
+                Some(if key == value { key } else { format!("{}: {}", key, value) })
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{ {} }}", entries.join(", "))
+        }
+    }
+
+    fn is_blank(node: &Handle) -> bool {
+        match &node.data {
+            NodeData::Text { contents } => contents.borrow().trim().is_empty(),
+            NodeData::Element { .. } => false,
+            _ => true,
+        }
+    }
+
+    fn walk_children(node: &Handle, indent: usize, out: &mut String, ctx: &mut JsxContext) {
+        walk_nodes(&node.children.borrow(), indent, out, ctx);
+    }
+
+    fn walk_nodes(children: &[Handle], indent: usize, out: &mut String, ctx: &mut JsxContext) {
+        let mut i = 0;
+        while i < children.len() {
+            let child = &children[i];
+            i += 1;
+            // a:for 优先级高于 a:if，同一节点上的 a:if 在循环体内处理
+            let cond = attr_value(child, "a:if").filter(|_| attr_value(child, "a:for").is_none());
+            let Some(cond) = cond else {
+                // 没有对应 a:if 的分支仍然输出，与小程序的行为不同，需要提示
+                if let Some(directive) = ["a:elif", "a:else"].into_iter().find(|name| attr_value(child, name).is_some()) {
+                    ctx.warn(directive, "directive without a preceding a:if, rendered unconditionally");
+                }
+                walk(child, indent, out, ctx);
+                continue;
+            };
+
+            // 收集紧随其后的 a:elif / a:else 兄弟节点
+            let mut branches = vec![(Some(cond), child.clone())];
+            let mut next = i;
+            loop {
+                while next < children.len() && is_blank(&children[next]) {
+                    next += 1;
+                }
+                let Some(sibling) = children.get(next) else {
+                    break;
                 };
-                events.insert(value.to_string());
-                Some((event.to_string(), format!("{{{}}}", value)))
+                if let Some(cond) = attr_value(sibling, "a:elif") {
+                    branches.push((Some(cond), sibling.clone()));
+                    next += 1;
+                    i = next;
+                } else if attr_value(sibling, "a:else").is_some() {
+                    branches.push((None, sibling.clone()));
+                    i = next + 1;
+                    break;
+                } else {
+                    break;
+                }
             }
-            _ => Some((name.to_string(), format!(r#"\"{}\""#, value))),
+            walk_conditional(&branches, indent, out, ctx);
         }
     }
 
-    fn convert_tag(tag: &str) -> &str {
-        match tag {
-            "view" => "div",
-            "text" => "span",
-            "image" => "img",
-            _ => tag,
+    fn walk_conditional(
+        branches: &[(Option<String>, Handle)],
+        indent: usize,
+        out: &mut String,
+        ctx: &mut JsxContext,
+    ) {
+        let indent_str = " ".repeat(indent);
+        // 没有 else 分支时输出 null，`&&` 会把 0、空字符串等假值渲染出来
+        if let [(Some(cond), node)] = branches {
+            let cond = ctx.expression(cond, "false");
+            out.push_str(&format!("{}{{{} ? (\n", indent_str, cond));
+            walk(node, indent + 2, out, ctx);
+            out.push_str(&format!("{}) : null}}\n", indent_str));
+            return;
+        }
+
+        for (idx, (cond, node)) in branches.iter().enumerate() {
+            let prefix = if idx == 0 {
+                format!("{}{{", indent_str)
+            } else {
+                format!("{}) : ", indent_str)
+            };
+            match cond {
+                Some(cond) => {
+                    let cond = ctx.expression(cond, "false");
+                    out.push_str(&format!("{}{} ? (\n", prefix, cond));
+                }
+                None => out.push_str(&format!("{}(\n", prefix)),
+            }
+            walk(node, indent + 2, out, ctx);
+        }
+        if branches.last().is_some_and(|(cond, _)| cond.is_none()) {
+            out.push_str(&format!("{})}}\n", indent_str));
+        } else {
+            out.push_str(&format!("{}) : null}}\n", indent_str));
         }
     }
 
-    fn walk(node: &Handle, indent: usize, out: &mut String, events: &mut HashSet<String>) {
+    fn walk(node: &Handle, indent: usize, out: &mut String, ctx: &mut JsxContext) {
         match &node.data {
             NodeData::Text { contents } => {
                 let text = contents.borrow();
                 let text = text.trim();
                 if !text.is_empty() {
+                    let text = to_jsx_text(text, &ctx.scope).unwrap_or_else(|reason| {
+                        ctx.warn(text, &reason);
+                        format!("{{{}}}", untranslated_comment(text))
+                    });
                     out.push_str(&format!("{}{}\n", " ".repeat(indent), text));
                 }
             }
-            NodeData::Element { name, attrs, .. } => {
-                let tag_name = name.local.as_ref();
-                if tag_name == "import-sjs" {
-                    return;
+            NodeData::Element { .. } => match attr_value(node, "a:for") {
+                Some(list) => walk_for(node, &list, indent, out, ctx),
+                None => walk_element(node, None, indent, out, ctx),
+            },
+            _ => {}
+        }
+    }
+
+    fn walk_for(node: &Handle, list: &str, indent: usize, out: &mut String, ctx: &mut JsxContext) {
+        let list = ctx.expression(list, "[]");
+        let item = attr_value(node, "a:for-item").unwrap_or_else(|| "item".to_string());
+        let index = attr_value(node, "a:for-index").unwrap_or_else(|| "index".to_string());
+        ctx.scope.push(&item);
+        ctx.scope.push(&index);
+
+        let key = match attr_value(node, "a:key") {
+            Some(key) if key == "*this" => item.clone(),
+            Some(key) if key.contains("{{") => ctx.expression(&key, &index),
+            Some(key) => format!("{}.{}", item, key),
+            None => index.clone(),
+        };
+
+        let indent_str = " ".repeat(indent);
+        let (body, end) = match attr_value(node, "a:if") {
+            Some(cond) => (format!("{} ? (", ctx.expression(&cond, "false")), ") : null"),
+            None => ("(".to_string(), ")"),
+        };
+        out.push_str(&format!("{}{{{}.map(({}, {}) => {}\n", indent_str, list, item, index, body));
+        walk_element(node, Some(&key), indent + 2, out, ctx);
+        out.push_str(&format!("{}{})}}\n", indent_str, end));
+
+        ctx.scope.pop();
+        ctx.scope.pop();
+    }
+
+    fn walk_element(
+        node: &Handle,
+        key: Option<&str>,
+        indent: usize,
+        out: &mut String,
+        ctx: &mut JsxContext,
+    ) {
+        let NodeData::Element { name, attrs, .. } = &node.data else {
+            return;
+        };
+        let tag_name = name.local.as_ref();
+        if matches!(tag_name, "import-sjs" | "import") {
+            return;
+        }
+        let indent_str = " ".repeat(indent);
+        if tag_name == "include" {
+            if let Some(src) = attr_value(node, "src") {
+                walk_include(&src, indent, out, ctx);
+            }
+            return;
+        }
+        if tag_name == "template" {
+            // 具名模板单独生成组件，这里只处理模板引用
+            if attr_value(node, "name").is_some() {
+                return;
+            }
+            if let Some(is) = attr_value(node, "is") {
+                walk_template_usage(node, &is, indent, out, ctx);
+            } else if let Some(contents) = template_contents(node) {
+                out.push_str(&format!("{}<>\n", indent_str));
+                walk_children(&contents, indent + 2, out, ctx);
+                out.push_str(&format!("{}</>\n", indent_str));
+            }
+            return;
+        }
+        // block 只是逻辑分组，输出为 Fragment
+        if tag_name == "block" {
+            match key {
+                Some(key) => out.push_str(&format!("{}<React.Fragment key={{{}}}>\n", indent_str, key)),
+                None => out.push_str(&format!("{}<>\n", indent_str)),
+            }
+            walk_children(node, indent + 2, out, ctx);
+            match key {
+                Some(_) => out.push_str(&format!("{}</React.Fragment>\n", indent_str)),
+                None => out.push_str(&format!("{}</>\n", indent_str)),
+            }
+            return;
+        }
+        if tag_name == "slot" {
+            walk_slot(node, indent, out, ctx);
+            return;
+        }
+        let component = ctx.components.get(tag_name).cloned();
+        let builtin = find_builtin(tag_name).filter(|_| component.is_none());
+        let jsx_tag = match (&component, builtin) {
+            (Some(component), _) => component.clone(),
+            (None, Some(builtin)) => {
+                if builtin.is_runtime() {
+                    ctx.runtime_imports.insert(builtin.jsx_tag());
+                }
+                builtin.jsx_tag().to_string()
+            }
+            (None, None) => {
+                ctx.warn(tag_name, "unknown component");
+                tag_name.to_string()
+            }
+        };
+        // 自定义组件和运行时组件的属性按驼峰 props 名传递
+        let camel_props = component.is_some() || builtin.is_some_and(|b| b.is_runtime());
+        let event_target = match builtin {
+            _ if component.is_some() => EventTarget::Component,
+            Some(builtin) if builtin.is_runtime() => EventTarget::Runtime,
+            _ => EventTarget::Dom,
+        };
+        let mut props = vec![];
+        if let Some(key) = key {
+            props.push(format!("key={{{}}} ", key));
+        }
+        let mut dataset = None;
+        // 已输出的事件属性在 props 中的位置、绑定和回调
+        let mut bindings: Vec<(usize, EventBinding, Vec<String>)> = vec![];
+        for attr in attrs.borrow().iter() {
+            let attr_name = attr.name.local.as_ref();
+            if DIRECTIVES.contains(&attr_name) || matches!(attr_name, "slot" | "slot-scope") {
+                continue;
+            }
+            let original = ctx.attr_names.get(attr_name).cloned().unwrap_or_else(|| attr_name.to_string());
+            match parse_event(&original, event_target) {
+                Some(Ok(binding)) => {
+                    let Some(handler) = event_value(&attr.value, ctx) else {
+                        continue;
+                    };
+                    let dataset = dataset.get_or_insert_with(|| element_dataset(&attrs.borrow(), ctx));
+                    let index = match bindings.iter().position(|(_, bound, _)| bound.prop == binding.prop) {
+                        Some(index) => index,
+                        None => {
+                            props.push(String::new());
+                            bindings.push((props.len() - 1, binding.clone(), vec![]));
+                            bindings.len() - 1
+                        }
+                    };
+                    let (prop_index, bound, handlers) = &mut bindings[index];
+                    bound.catch |= binding.catch;
+                    handlers.push(handler);
+                    let handler = event_handler(bound, handlers, event_target, dataset, ctx);
+                    props[*prop_index] = format!("{}={{{}}} ", bound.prop, handler);
+                    continue;
                 }
-                let jsx_tag = convert_tag(tag_name);
-                let mut props = vec![];
-                for attr in attrs.borrow().iter() {
-                    if let Some((k, v)) = convert_attr(attr.name.local.as_ref(), &attr.value, events) {
-                        props.push(format!("{}={} ", k, v));
-                    }
+                Some(Err(reason)) => {
+                    ctx.warn(&original, &reason);
+                    continue;
                 }
-                let children = &node.children.borrow();
-                let has_children = children.iter().any(|c| matches!(c.data, NodeData::Text { .. } | NodeData::Element { .. }));
-                let indent_str = " ".repeat(indent);
-                if has_children {
-                    out.push_str(&format!("{}<{} {}>\n", indent_str, jsx_tag, props.concat()));
-                    for child in children.iter() {
-                        walk(child, indent + 2, out, events);
-                    }
-                    out.push_str(&format!("{}</{}>\n", indent_str, jsx_tag));
-                } else {
-                    out.push_str(&format!("{}<{} {}/>\n", indent_str, jsx_tag, props.concat()));
+                None => {}
+            }
+            // ref 的值是接收节点或组件实例的方法，自定义组件通过 componentRef 传递实例
+            if attr_name == "ref" {
+                let Some(handler) = event_value(&attr.value, ctx) else {
+                    continue;
+                };
+                let prop = if component.is_some() { "componentRef" } else { "ref" };
+                props.push(format!("{}={{{}}} ", prop, handler));
+                continue;
+            }
+            // 传给自定义组件 externalClasses 的类名同样按当前组件的样式转换
+            if component.is_some() && ctx.module_classes.is_some() && original.ends_with("-class") {
+                if let Some(value) = module_class_name(&attr.value, ctx) {
+                    props.push(format!("{}={} ", to_lower_camel_case(&original), value));
+                    continue;
                 }
             }
-            _ => {}
+            let attr_name = match builtin.and_then(|b| b.map_prop(attr_name)) {
+                Some("") => continue,
+                Some(mapped) => mapped.to_string(),
+                None if camel_props && !attr_name.starts_with("data-") => to_lower_camel_case(&original),
+                None => attr_name.to_string(),
+            };
+            // 没有值的属性在小程序中表示 true
+            if attr.value.is_empty() && (camel_props || BOOLEAN_ATTRS.contains(&attr_name.as_str())) {
+                props.push(format!("{} ", attr_name));
+                continue;
+            }
+            if let Some((k, v)) = convert_attr(&attr_name, &attr.value, ctx) {
+                props.push(format!("{}={} ", k, v));
+            }
         }
+
+        // 带 slot 属性的子节点转换为同名 prop，其余子节点作为 children
+        let mut slots: Vec<(String, Option<String>, Vec<Handle>)> = vec![];
+        let mut children = vec![];
+        for child in node.children.borrow().iter() {
+            let Some(slot) = attr_value(child, "slot") else {
+                children.push(child.clone());
+                continue;
+            };
+            let slot = slot_prop_name(&slot);
+            match slots.iter_mut().find(|(name, _, _)| *name == slot) {
+                Some((_, _, nodes)) => nodes.push(child.clone()),
+                None => slots.push((slot, attr_value(child, "slot-scope"), vec![child.clone()])),
+            }
+        }
+        for (name, slot_scope, nodes) in &slots {
+            props.push(format!("{}={} ", name, slot_prop_value(nodes, slot_scope.as_deref(), indent, ctx)));
+        }
+
+        let has_children = children.iter().any(|c| !is_blank(c));
+        if has_children {
+            out.push_str(&format!("{}<{} {}>\n", indent_str, jsx_tag, props.concat()));
+            walk_nodes(&children, indent + 2, out, ctx);
+            out.push_str(&format!("{}</{}>\n", indent_str, jsx_tag));
+        } else {
+            out.push_str(&format!("{}<{} {}/>\n", indent_str, jsx_tag, props.concat()));
+        }
+    }
+
+    // 具名插槽对应 props 上的同名属性，带参数的插槽按 render prop 调用
+    fn walk_slot(node: &Handle, indent: usize, out: &mut String, ctx: &mut JsxContext) {
+        let NodeData::Element { attrs, .. } = &node.data else {
+            return;
+        };
+        let prop = match attr_value(node, "name") {
+            Some(name) => slot_prop_name(&name),
+            None => "children".to_string(),
+        };
+        // 模板组件只有 data 参数，插槽内容需要通过模板数据传入
+        let prop = if ctx.in_template {
+            ctx.warn(&prop, "slot inside a template is read from the template data");
+            format!("data.{}", prop)
+        } else {
+            ctx.expression(&format!("{{{{props.{}}}}}", prop), "undefined")
+        };
+        let scope_props = attrs
+            .borrow()
+            .iter()
+            .filter(|attr| attr.name.local.as_ref() != "name")
+            .map(|attr| (to_lower_camel_case(attr.name.local.as_ref()), attr.value.to_string()))
+            .collect::<Vec<_>>();
+        let value = if scope_props.is_empty() {
+            prop
+        } else {
+            ctx.scoped_slots = true;
+            let scope_props = scope_props
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, ctx.expression(value, "undefined")))
+                .collect::<Vec<_>>();
+            format!("renderSlot({}, {{ {} }})", prop, scope_props.join(", "))
+        };
+
+        let indent_str = " ".repeat(indent);
+        if node.children.borrow().iter().all(is_blank) {
+            out.push_str(&format!("{}{{{}}}\n", indent_str, value));
+        } else {
+            // slot 的子节点是未传入时的默认内容
+            out.push_str(&format!("{}{{{} ?? (\n{}  <>\n", indent_str, value, indent_str));
+            walk_children(node, indent + 4, out, ctx);
+            out.push_str(&format!("{}  </>\n{})}}\n", indent_str, indent_str));
+        }
+    }
+
+    fn slot_prop_value(nodes: &[Handle], slot_scope: Option<&str>, indent: usize, ctx: &mut JsxContext) -> String {
+        let indent_str = " ".repeat(indent);
+        let mut content = String::new();
+        match slot_scope {
+            Some(slot_scope) => {
+                ctx.scope.push(slot_scope);
+                walk_nodes(nodes, indent + 6, &mut content, ctx);
+                ctx.scope.pop();
+                format!(
+                    "{{({}) => (\n{}    <>\n{}{}    </>\n{}  )}}",
+                    slot_scope, indent_str, content, indent_str, indent_str
+                )
+            }
+            None => {
+                walk_nodes(nodes, indent + 4, &mut content, ctx);
+                format!("{{<>\n{}{}  </>}}", content, indent_str)
+            }
+        }
+    }
+
+    // include 相当于把目标文件中除 template 定义外的内容原样拷贝到当前位置
+    fn walk_include(src: &str, indent: usize, out: &mut String, ctx: &mut JsxContext) {
+        let base = ctx.files.last().cloned().unwrap_or_default();
+        let Some(path) = resolve_axml_src(&base, src) else {
+            ctx.warn(src, "include file not found");
+            return;
+        };
+        if ctx.files.contains(&path) {
+            ctx.warn(src, "circular include");
+            return;
+        }
+        let dom = parse_axml(&fs::read_to_string(&path).unwrap_or_default());
+        ctx.files.push(path);
+        for child in dom.document.children.borrow().iter() {
+            walk_children(child, indent, out, ctx);
+        }
+        ctx.files.pop();
+    }
+
+    // 模板定义在组件外，模板中的事件回调由这里作为 data 的同名字段传入
+    fn walk_template_usage(node: &Handle, is: &str, indent: usize, out: &mut String, ctx: &mut JsxContext) {
+        let data = match attr_value(node, "data") {
+            Some(data) => template_data(&data, ctx),
+            None => "{}".to_string(),
+        };
+        let indent_str = " ".repeat(indent);
+        let handlers = match is.contains("{{") {
+            true => ctx.template_handlers.values().flatten().cloned().collect::<BTreeSet<_>>(),
+            false => ctx.template_handlers.get(is).cloned().unwrap_or_default(),
+        };
+        let handlers = handlers
+            .into_iter()
+            .filter_map(|name| event_value(&name, ctx).map(|value| (name, value)))
+            .collect::<Vec<_>>();
+        if is.contains("{{") {
+            ctx.dynamic_templates = true;
+            let name = ctx.expression(is, "\"\"");
+            let data = match handlers.is_empty() {
+                true => data,
+                false => {
+                    let entries = handlers
+                        .iter()
+                        .map(|(name, value)| if name == value { name.clone() } else { format!("{}: {}", name, value) })
+                        .collect::<Vec<_>>();
+                    format!("{{ ...{}, {} }}", data, entries.join(", "))
+                }
+            };
+            out.push_str(&format!("{}{{renderTemplate({}, {})}}\n", indent_str, name, data));
+        } else {
+            let handlers = handlers.iter().map(|(name, value)| format!("{}={{{}}} ", name, value)).collect::<String>();
+            out.push_str(&format!("{}<{} {{...{}}} {}/>\n", indent_str, template_component_name(is), data, handlers));
+        }
+    }
+
+    // data="{{...item, index}}" 是省略了花括号的对象字面量
+    fn template_data(value: &str, ctx: &mut JsxContext) -> String {
+        let expr = match parse_mustache(value).as_slice() {
+            [Segment::Dynamic(expr)] => expr.clone(),
+            _ => {
+                ctx.warn(value, "template data must be a single {{ }} expression");
+                return "{}".to_string();
+            }
+        };
+        let translated = match expr.strip_prefix("...").filter(|inner| !inner.contains(',')) {
+            Some(inner) => translate_expression(inner, &ctx.scope),
+            None => translate_expression(&format!("{{{}}}", expr), &ctx.scope),
+        };
+        translated.unwrap_or_else(|reason| {
+            ctx.warn(value, &reason);
+            format!("{{}} {}", untranslated_comment(value))
+        })
     }
 
+    // 模板中以方法名绑定的事件回调和 ref
+    fn collect_template_handlers(node: &Handle, attr_names: &HashMap<String, String>, out: &mut BTreeSet<String>) {
+        if let NodeData::Element { attrs, .. } = &node.data {
+            for attr in attrs.borrow().iter() {
+                let name = attr.name.local.as_ref();
+                let original = attr_names.get(name).map_or(name, String::as_str);
+                let value = attr.value.trim();
+                let is_handler = name == "ref" || parse_event(original, EventTarget::Component).is_some();
+                if is_handler && !value.is_empty() && !value.contains("{{") {
+                    out.insert(value.to_string());
+                }
+            }
+        }
+        if let Some(contents) = template_contents(node) {
+            collect_template_handlers(&contents, attr_names, out);
+        }
+        for child in node.children.borrow().iter() {
+            collect_template_handlers(child, attr_names, out);
+        }
+    }
+
+    fn collect_templates(node: &Handle, out: &mut Vec<(String, Handle)>) {
+        if let NodeData::Element { ref name, .. } = node.data {
+            if name.local.as_ref() == "template" {
+                if let (Some(name), Some(contents)) = (attr_value(node, "name"), template_contents(node)) {
+                    out.push((name, contents));
+                }
+                return;
+            }
+        }
+        for child in node.children.borrow().iter() {
+            collect_templates(child, out);
+        }
+    }
+
+    fn collect_template_imports(node: &Handle, out: &mut Vec<String>) {
+        if let NodeData::Element { ref name, .. } = node.data {
+            if name.local.as_ref() == "import" {
+                if let Some(src) = attr_value(node, "src") {
+                    out.push(src);
+                }
+            }
+        }
+        for child in node.children.borrow().iter() {
+            collect_template_imports(child, out);
+        }
+    }
+
+    fn collect_sjs_imports(node: &Handle, out: &mut Vec<(String, String)>) {
+        if let NodeData::Element { ref name, .. } = node.data {
+            if name.local.as_ref() == "import-sjs" {
+                if let (Some(name), Some(from)) = (attr_value(node, "name"), attr_value(node, "from")) {
+                    out.push((name, from));
+                }
+            }
+        }
+        for child in node.children.borrow().iter() {
+            collect_sjs_imports(child, out);
+        }
+    }
+
+    // usingComponents 中的组件生成 import，并把对应标签替换为组件名
+    let mut component_imports = vec![];
+    for (tag, value) in extract_using_components(axml_path) {
+        let Some(module) = component_module(axml_path, &value) else {
+            ctx.warn(&value, "component path cannot be imported");
+            continue;
+        };
+        let name = to_camel_case(&tag);
+        component_imports.push(format!("import {} from \"{}\";\n", name, module));
+        ctx.components.insert(tag.to_ascii_lowercase(), name);
+    }
+
+    // sjs 模块名在表达式中按局部变量处理
+    let mut sjs_imports = vec![];
+    collect_sjs_imports(&dom.document, &mut sjs_imports);
+    for (name, _) in &sjs_imports {
+        ctx.scope.push(name);
+    }
+
+    // 具名模板生成为接收 data 作为 props 的函数组件
+    let mut templates = vec![];
+    collect_templates(&dom.document, &mut templates);
+    let mut template_names = templates.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+    for (name, contents) in &templates {
+        let handlers = ctx.template_handlers.entry(name.clone()).or_default();
+        collect_template_handlers(contents, &ctx.attr_names, handlers);
+    }
+
+    // import 引入的模板来自目标文件生成的具名导出
+    let mut import_srcs = vec![];
+    collect_template_imports(&dom.document, &mut import_srcs);
+    let mut template_imports = vec![];
+    for src in &import_srcs {
+        let Some(path) = resolve_axml_src(axml_path, src) else {
+            ctx.warn(src, "imported template file not found");
+            continue;
+        };
+        let imported_content = fs::read_to_string(&path).unwrap_or_default();
+        let imported_dom = parse_axml(&imported_content);
+        let mut imported = vec![];
+        collect_templates(&imported_dom.document, &mut imported);
+        if imported.is_empty() {
+            continue;
+        }
+        let imported_attr_names = collect_attr_names(&imported_content);
+        for (name, contents) in &imported {
+            let handlers = ctx.template_handlers.entry(name.clone()).or_default();
+            collect_template_handlers(contents, &imported_attr_names, handlers);
+        }
+        let names = imported.iter().map(|(name, _)| template_component_name(name)).collect::<Vec<_>>();
+        let module = relative_module(axml_path.parent().unwrap_or(axml_path), &path.with_extension(""));
+        template_imports.push(format!("import {{ {} }} from \"{}\";\n", names.join(", "), module));
+        template_names.extend(imported.into_iter().map(|(name, _)| name));
+    }
+
+    let mut template_functions = vec![];
+    ctx.in_template = true;
+    for (name, contents) in &templates {
+        let mut body = String::new();
+        walk_children(contents, 6, &mut body, &mut ctx);
+        template_functions.push(format!(
+            "export function {}(data: any) {{\n  return (\n    <>\n{}    </>\n  );\n}}",
+            template_component_name(name),
+            body
+        ));
+    }
+    ctx.in_template = false;
+
+    let script_path = [axml_path.with_extension("js"), axml_path.with_extension("ts")]
+        .into_iter()
+        .find(|p| p.exists());
+    let script = script_path.as_deref().map(load_script).unwrap_or_default();
+    for warning in &script.warnings {
+        eprintln!("{:?}: {}", script_path.as_deref().unwrap_or(axml_path), warning);
+    }
+    // 组件模板中的自由标识符是组件状态，模板片段中的仍然来自 data 参数
+    let declared_state = script.state_names();
+    ctx.scope.use_state(&declared_state.iter().cloned().collect::<Vec<_>>());
+    // 与状态同名的 prop 不解构，模板和脚本中通过 props.x 访问
+    let prop_names = script
+        .props
+        .iter()
+        .map(|prop| prop.name.clone())
+        .filter(|name| !declared_state.contains(name))
+        .collect::<Vec<_>>();
+    ctx.scope.declare_props(&prop_names);
+    // externalClasses 中的类名由父组件通过同名 prop 传入
+    let external_classes = script.options.iter().find(|option| option.name == "externalClasses");
+    if let (Some(option), Some(_)) = (external_classes, &ctx.module_classes) {
+        let re = Regex::new(r#"["'`]([^"'`]+)["'`]"#).unwrap();
+        for cap in re.captures_iter(&option.value) {
+            let prop = to_lower_camel_case(&cap[1]);
+            let prop = if prop_names.contains(&prop) { prop } else { format!("props.{}", prop) };
+            ctx.external_classes.push((cap[1].to_string(), prop));
+        }
+    }
     let mut jsx = String::new();
     for child in dom.document.children.borrow().iter() {
-        walk(child, 2, &mut jsx, &mut events);
+        walk_children(child, 6, &mut jsx, &mut ctx);
+    }
+
+    if ctx.dynamic_templates {
+        let entries = template_names
+            .iter()
+            .map(|name| format!("  {}: {},\n", quote_string(name), template_component_name(name)))
+            .collect::<String>();
+        template_functions.push(format!(
+            "const templates: Record<string, React.ComponentType<any>> = {{\n{}}};\n\n\
+function renderTemplate(name: string, data: any) {{\n  const Template = templates[name];\n  return Template ? <Template {{...data}} /> : null;\n}}",
+            entries
+        ));
+    }
+
+    if ctx.scoped_slots {
+        template_functions.push(
+            "function renderSlot(slot: any, scope: any) {\n  return typeof slot === \"function\" ? slot(scope) : slot;\n}"
+                .to_string(),
+        );
+    }
+
+    for warning in &ctx.warnings {
+        eprintln!("{:?}: {}", axml_path, warning);
     }
 
     let component_name = axml_path
@@ -315,35 +1587,742 @@ fn convert_axml_to_jsx(axml_path: &Path) -> String {
         .and_then(|n| n.to_str())
         .unwrap_or("Component");
 
-    let script_path = axml_path.with_file_name("index.js");
-    let ts_script_path = axml_path.with_file_name("index.ts");
-    let method_functions = if script_path.exists() {
-        extract_methods_from_script(&script_path)
-    } else if ts_script_path.exists() {
-        extract_methods_from_script(&ts_script_path)
-    } else {
-        vec![]
-    };
+    ctx.runtime_imports.extend(script.runtime_imports.iter().copied());
+    let mut react_hooks = BTreeSet::new();
+    let mut hooks = vec![];
+    // 组件内的 getApp() 改为读取 AppProvider 提供的应用实例
+    if script.uses_app {
+        ctx.runtime_imports.insert("useApp");
+        hooks.push("const appInstance = useApp();".to_string());
+    }
+    if script.declarations_use_app {
+        ctx.runtime_imports.insert("getApp");
+    }
+
+    // 模板中未声明的标识符不会生成状态，需要在 data 中补充声明
+    for name in ctx.scope.undeclared() {
+        eprintln!("{:?}: identifier is not declared in data or props: `{}`", axml_path, name);
+    }
+    // data 中声明的字段在前，其余是只在 setData 或 data 函数中出现的字段
+    let mut state = script
+        .data
+        .iter()
+        .map(|field| (field.name.clone(), field.comments.clone(), Some(field.value.clone())))
+        .collect::<Vec<_>>();
+    for name in &script.state {
+        if !state.iter().any(|(field, _, _)| field == name) {
+            state.push((name.clone(), String::new(), None));
+        }
+    }
+    state.retain(|(name, _, _)| {
+        let valid = is_identifier(name);
+        if !valid {
+            eprintln!("{:?}: data field is not a valid identifier: `{}`", axml_path, name);
+        }
+        valid
+    });
+    for (name, _, _) in &state {
+        if script.method(name).is_some() || ctx.events.contains(name) {
+            eprintln!("{:?}: data field conflicts with a method of the same name: `{}`", axml_path, name);
+        }
+        if script.props.iter().any(|prop| prop.name == *name) {
+            eprintln!("{:?}: data field shadows a prop of the same name, read the prop as props.{}", axml_path, name);
+        }
+    }
+    if let Some(factory) = &script.data_factory {
+        hooks.push(format!("const [initialData] = useState((): Record<string, any> => {});", factory));
+    }
+    for (name, comments, value) in &state {
+        // 只在 setData 中出现的同名字段以 prop 的值作为初始值
+        let shadowed = script.props.iter().find(|prop| prop.name == *name);
+        let value = match (value, &script.data_factory, shadowed) {
+            (Some(value), _, _) => value.clone(),
+            (None, Some(_), _) => format!("initialData.{}", name),
+            (None, None, Some(prop)) => format!("props.{} ?? {}", name, prop.default),
+            (None, None, None) => String::new(),
+        };
+        let state_type = if is_primitive_literal(&value) { "" } else { "<any>" };
+        hooks.push(format!(
+            "{}const [{}, {}] = useState{}({});",
+            comments,
+            name,
+            state_setter(name),
+            state_type,
+            value
+        ));
+    }
+    if !state.is_empty() || script.data_factory.is_some() {
+        react_hooks.insert("useState");
+    }
+    let has_lifecycle = |name: &str| !script.lifecycle_calls(name).is_empty();
+    let tracks_props = has_lifecycle("didUpdate") || has_lifecycle("deriveDataFromProps");
+    if script.uses_data || has_lifecycle("didUpdate") {
+        let names = state.iter().map(|(name, _, _)| name.as_str()).collect::<Vec<_>>();
+        hooks.push(format!("const data = {{ {} }};", names.join(", ")));
+    }
+    if script.uses_props || (tracks_props && !prop_names.is_empty()) {
+        hooks.push(format!("const allProps = {{ ...props, {} }};", prop_names.join(", ")));
+    }
+    if script.uses_setters {
+        let setters = state
+            .iter()
+            .map(|(name, _, _)| format!("{}: {}", name, state_setter(name)))
+            .collect::<Vec<_>>();
+        hooks.push(format!(
+            "const stateSetters: Record<string, (value: any) => void> = {{ {} }};",
+            setters.join(", ")
+        ));
+    }
+    // 无法对应到状态的实例属性保存在 ref 上，在多次渲染间保持不变
+    if script.uses_instance {
+        react_hooks.insert("useRef");
+        hooks.push("const instance = useRef<Record<string, any>>({}).current;".to_string());
+    }
 
-    let stub_funcs = events.iter()
-        .filter(|e| !method_functions.iter().any(|m| m.contains(&format!("function {}", e))))
+    let mut events = ctx.events.iter().collect::<Vec<_>>();
+    events.sort();
+    let stub_funcs = events
+        .into_iter()
+        .filter(|e| script.method(e).is_none())
         .map(|e| format!("function {}(e) {{\n  // TODO: implement {}\n}}", e, e))
         .collect::<Vec<_>>();
+    let component_functions = script
+        .methods
+        .iter()
+        .chain(script.mixin_lifecycles.iter().map(|(_, method)| method))
+        .chain(&script.lifecycles)
+        .chain(script.observers.iter().map(|observer| &observer.method))
+        .map(|method| method.to_function())
+        .chain(stub_funcs)
+        .collect::<Vec<_>>();
 
-    let all_functions = [method_functions, stub_funcs].concat().join("\n\n");
+    // 生命周期函数保留为组件内的同名函数，由对应的 effect 调用
+    let script_file = script_path.as_deref().unwrap_or(axml_path);
+    let mut effects = vec![];
+    let all_props = if prop_names.is_empty() { "props" } else { "allProps" };
+    // mixin 的同名生命周期在组件自身之前注册
+    let mut lifecycles = script.mixin_lifecycles.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+    lifecycles.extend(script.lifecycles.iter().map(|method| method.name.as_str()));
+    let mut seen = HashSet::new();
+    lifecycles.retain(|name| seen.insert(*name));
+    for lifecycle in lifecycles {
+        match lifecycle {
+            "onInit" => eprintln!("{:?}: onInit runs after the first render instead of before it", script_file),
+            "deriveDataFromProps" => {
+                eprintln!(
+                    "{:?}: deriveDataFromProps runs during render, side effects other than setData should move into effects",
+                    script_file
+                );
+                ctx.runtime_imports.insert("useDerivedData");
+                for call in script.lifecycle_calls(lifecycle) {
+                    effects.push(format!("useDerivedData({}, {});", all_props, call));
+                }
+            }
+            "didUpdate" => {
+                ctx.runtime_imports.insert("useDidUpdate");
+                for call in script.lifecycle_calls(lifecycle) {
+                    effects.push(format!("useDidUpdate({}, data, {});", all_props, call));
+                }
+            }
+            "didMount" | "didUnmount" => {}
+            name if PAGE_HOOKS.iter().any(|(lifecycle, _)| *lifecycle == name) => {}
+            other => eprintln!("{:?}: lifecycle is not supported: `{}`", script_file, other),
+        }
+    }
+    // 页面生命周期按 onLoad、onShow、onReady 的顺序注册
+    for (name, hook) in PAGE_HOOKS {
+        if has_lifecycle(name) {
+            ctx.runtime_imports.insert(hook);
+            for call in script.lifecycle_calls(name) {
+                effects.push(format!("{}({});", hook, call));
+            }
+        }
+    }
+    // observers 以字段的值为依赖，`**` 依赖全部状态和 props
+    for observer in &script.observers {
+        let mut args = vec![];
+        let mut deps = vec![];
+        for field in &observer.fields {
+            match field {
+                ObservedField::All => {
+                    args.push("data".to_string());
+                    deps.extend(state.iter().map(|(name, _, _)| name.clone()));
+                    deps.extend(prop_names.iter().cloned());
+                }
+                ObservedField::Path(_, expr) => {
+                    args.push(expr.clone());
+                    deps.push(expr.clone());
+                }
+            }
+        }
+        let mut seen = HashSet::new();
+        deps.retain(|dep| seen.insert(dep.clone()));
+        ctx.runtime_imports.insert("useObserver");
+        effects.push(format!(
+            "useObserver(() => {}({}), [{}]);",
+            observer.method.name,
+            args.join(", "),
+            deps.join(", ")
+        ));
+    }
+    // 组件方法和数据作为实例提供给父组件的 ref 和 $selectComponent
+    if script.kind == Some(ScriptKind::Component) && !script.methods.is_empty() {
+        let mut members = vec![];
+        if !state.is_empty() {
+            let names = state.iter().map(|(name, _, _)| name.as_str()).collect::<Vec<_>>();
+            members.push(format!("data: {{ {} }}", names.join(", ")));
+        }
+        members.extend(script.methods.iter().map(|method| method.name.clone()));
+        let instance_props = if prop_names.iter().any(|name| name == "id") { "{ ...props, id }" } else { "props" };
+        ctx.runtime_imports.insert("useComponentInstance");
+        effects.insert(0, format!("useComponentInstance({}, {{ {} }});", instance_props, members.join(", ")));
+    }
+    let mount = ["onInit", "didMount"]
+        .into_iter()
+        .flat_map(|name| script.lifecycle_calls(name))
+        .map(|call| format!("  {}();\n", call))
+        .collect::<String>();
+    let unmount = script
+        .lifecycle_calls("didUnmount")
+        .into_iter()
+        .map(|call| format!("    {}();\n", call))
+        .collect::<String>();
+    let cleanup = if unmount.is_empty() {
+        String::new()
+    } else {
+        format!("  return () => {{\n{}  }};\n", unmount)
+    };
+    if !mount.is_empty() || !cleanup.is_empty() {
+        react_hooks.insert("useEffect");
+        effects.insert(0, format!("useEffect(() => {{\n{}{}}}, []);", mount, cleanup));
+    }
 
-    format!(
-        "import React from \"react\";
+    let module_functions = [template_functions, script.declarations].concat().join("\n\n");
+    let mut body = hooks.iter().map(|hook| format!("  {}\n", reindent(hook, 2))).collect::<String>();
+    for function in &component_functions {
+        if !body.is_empty() {
+            body.push('\n');
+        }
+        body.push_str(&format!("  {}\n", reindent(function, 2)));
+    }
+    if !effects.is_empty() {
+        if !body.is_empty() {
+            body.push('\n');
+        }
+        body.extend(effects.iter().map(|effect| format!("  {}\n", reindent(effect, 2))));
+    }
+    if !body.is_empty() {
+        body.push('\n');
+    }
 
+    let imports = sjs_imports
+        .iter()
+        .map(|(name, from)| format!("import {} from \"{}\";\n", name, from))
+        .chain(component_imports)
+        .chain(template_imports)
+        .chain(script.imports.iter().map(|import| format!("{}\n", import)))
+        .chain(style_import(axml_path, css_module.is_some()))
+        .collect::<String>();
+    let runtime_import = if ctx.runtime_imports.is_empty() {
+        String::new()
+    } else {
+        let names = ctx.runtime_imports.iter().copied().collect::<Vec<_>>();
+        format!("import {{ {} }} from \"{}\";\n", names.join(", "), runtime_module)
+    };
+
+    // 由 props 默认值推断的类型，未声明的属性（children、插槽、事件等）通过索引签名传入
+    let component_name = to_camel_case(component_name);
+    let (props_interface, props_param) = if script.props.is_empty() {
+        (String::new(), "props: any".to_string())
+    } else {
+        let fields = script
+            .props
+            .iter()
+            .map(|prop| {
+                let comments = prop.comments.lines().map(|line| format!("  {}\n", line)).collect::<String>();
+                format!("{}  {}?: {};\n", comments, prop.name, prop.ts_type)
+            })
+            .collect::<String>();
+        let interface = format!(
+            "export interface {}Props {{\n{}  [key: string]: any;\n}}\n\n",
+            component_name, fields
+        );
+        let defaults = script
+            .props
+            .iter()
+            .filter(|prop| prop_names.contains(&prop.name))
+            .map(|prop| format!("{} = {}", prop.name, prop.default))
+            .collect::<Vec<_>>();
+        let param = format!("{{ {}, ...props }}: {}Props", defaults.join(", "), component_name);
+        let param = if defaults.is_empty() {
+            format!("props: {}Props", component_name)
+        } else if param.len() > 80 || param.contains('\n') {
+            let defaults = defaults.iter().map(|default| format!("  {},\n", reindent(default, 2))).collect::<String>();
+            format!("{{\n{}  ...props\n}}: {}Props", defaults, component_name)
+        } else {
+            param
+        };
+        (interface, param)
+    };
+
+    let react_import = if react_hooks.is_empty() {
+        "import React from \"react\";\n".to_string()
+    } else {
+        let names = react_hooks.into_iter().collect::<Vec<_>>();
+        format!("import React, {{ {} }} from \"react\";\n", names.join(", "))
+    };
+
+    // 只定义模板的文件只导出模板组件，不生成默认导出的组件
+    if script_path.is_none() && !templates.is_empty() && jsx.trim().is_empty() {
+        return format!("{}{}{}\n{}\n", react_import, runtime_import, imports, module_functions);
+    }
+
+    // `:host` 的样式作用在包裹组件内容的根节点上
+    let (open_root, close_root) = match css_module.as_ref().is_some_and(|stylesheet| stylesheet.host) {
+        true => ("<div className={styles.host}>", "</div>"),
+        false => ("<>", "</>"),
+    };
+    format!(
+        "{}{}{}
 {}
 
-export default function {}() {{
-  return (
-    <>\n{}    </>
+{}export default function {}({}) {{
+{}  return (
+    {}\n{}    {}
   );
 }}",
-        all_functions,
-        to_camel_case(component_name),
-        jsx
+        react_import,
+        runtime_import,
+        imports,
+        module_functions,
+        props_interface,
+        component_name,
+        props_param,
+        body,
+        open_root,
+        jsx,
+        close_root
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 在临时目录中写入各个文件，返回目录路径
+    fn write_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mini2react-{}", std::process::id())).join(name);
+        for (file, content) in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    /// 连续空白压缩为一个空格，只比较 JSX 的结构
+    fn compact(text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// 转换临时目录中组件的 index.axml
+    fn convert(name: &str, files: &[(&str, &str)]) -> String {
+        let dir = write_files(name, files);
+        let output = convert_axml_to_jsx(&dir.join("index.axml"), "../runtime", &StyleOptions::default());
+        fs::remove_dir_all(&dir).unwrap();
+        output
+    }
+
+    #[test]
+    fn named_and_scoped_slot_content_becomes_props() {
+        let axml = "<card>\n  <view slot=\"header\">Title</view>\n  \
+            <view slot=\"item\" slot-scope=\"scope\">{{scope.item.name}}</view>\n  <text>body</text>\n</card>";
+        let output = compact(&convert("slot-usage", &[
+            ("index.axml", axml),
+            ("index.json", "{ \"usingComponents\": { \"card\": \"./card/index\" } }"),
+            ("card/index.axml", "<view><slot /></view>"),
+        ]));
+        let expected = "<Card header={<> <div > Title </div> </>} \
+            item={(scope) => ( <> <div > {scope.item.name} </div> </> )} > <span > body </span> </Card>";
+        assert!(output.contains(expected), "{}", output);
+    }
+
+    #[test]
+    fn slots_render_props_with_scope_and_default_content() {
+        let axml = "<view><slot name=\"item\" item=\"{{list[0]}}\" /><slot>default</slot></view>";
+        let output = compact(&convert("slot-definition", &[
+            ("index.axml", axml),
+            ("index.js", "Component({ data: { list: [] } });\n"),
+        ]));
+        assert!(output.contains("function renderSlot(slot: any, scope: any) {"));
+        assert!(output.contains("{renderSlot(props.item, { item: list[0] })} {props.children ?? ( <> default </> )}"));
+    }
+
+    #[test]
+    fn slots_resolve_through_declared_props() {
+        let output = convert("slot-props", &[
+            (
+                "index.axml",
+                "<view><slot name=\"header\" /><slot name=\"footer\" /><slot /></view>\n\
+                 <template name=\"item\"><view><slot /></view></template>",
+            ),
+            ("index.js", "Component({ props: { header: null } });\n"),
+        ]);
+        assert!(output.contains("{header}\n"));
+        assert!(output.contains("{props.footer}\n"));
+        assert!(output.contains("{props.children}\n"));
+        assert!(output.contains("{data.children}\n"));
+    }
+
+    #[test]
+    fn imports_and_includes_resolve_relative_and_absolute_src() {
+        let axml = "<import src=\"../tpl/a.axml\" />\n<import src=\"/tpl/b\" />\n\
+            <include src=\"../tpl/part.axml\" />\n<template is=\"a\" data=\"{{a}}\" />";
+        let dir = write_files("import-include", &[
+            ("app.json", "{ \"pages\": [\"page/index\"] }"),
+            ("page/index.axml", axml),
+            ("page/index.js", "Page({ data: { a: 1, name: 'x' } });\n"),
+            ("tpl/a.axml", "<template name=\"a\"><view>{{a}}</view></template>"),
+            ("tpl/b.axml", "<template name=\"b\"><view>b</view></template>"),
+            ("tpl/part.axml", "<template name=\"skip\"><view /></template><view>{{name}}</view>"),
+        ]);
+        let output = convert_axml_to_jsx(&dir.join("page/index.axml"), "../runtime", &StyleOptions::default());
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(output.contains("import { ATemplate } from \"../tpl/a\";\n"));
+        assert!(output.contains("import { BTemplate } from \"../tpl/b\";\n"));
+        assert!(compact(&output).contains("<> <div > {name} </div> <ATemplate {...{a}} /> </>"), "{}", output);
+        assert!(!output.contains("Skip"));
+    }
+
+    #[test]
+    fn using_components_become_imports_and_pascal_case_tags() {
+        let dir = write_files("using-components", &[
+            ("app.json", "{ \"pages\": [\"page/index\"] }"),
+            (
+                "page/index.json",
+                concat!(
+                    "{ \"usingComponents\": ",
+                    "{ \"my-list\": \"./my-list/index\", \"ext-btn\": \"/components/button/index\" } }",
+                ),
+            ),
+            ("page/index.axml", "<view><my-list items=\"{{items}}\" /><ext-btn>ok</ext-btn></view>"),
+            ("page/index.js", "Page({ data: { items: [] } });\n"),
+        ]);
+        let output = convert_axml_to_jsx(&dir.join("page/index.axml"), "../runtime", &StyleOptions::default());
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(output.contains("import ExtBtn from \"../components/button\";\n"));
+        assert!(output.contains("import MyList from \"./my-list\";\n"));
+        assert!(compact(&output).contains("<MyList items={items} /> <ExtBtn > ok </ExtBtn>"), "{}", output);
+    }
+
+    #[test]
+    fn builtin_components_map_to_dom_and_runtime_imports() {
+        let axml = "<view class=\"a\" hover-class=\"pressed\"><scroll-view scroll-y>x</scroll-view>\
+            <label for=\"n\">n</label><button>b</button></view>";
+        let output = convert("builtin-components", &[("index.axml", axml)]);
+        assert!(output.contains("import { Button, ScrollView } from \"../runtime\";\n"));
+        let expected = "<div className=\"a\" > <ScrollView scrollY > x </ScrollView> \
+            <label htmlFor=\"n\" > n </label> <Button > b </Button> </div>";
+        assert!(compact(&output).contains(expected), "{}", output);
+    }
+
+    #[test]
+    fn event_handlers_receive_camel_cased_dataset() {
+        let axml = "<view data-item-id=\"{{id}}\" data-name=\"x\" onTap=\"select\" catchLongTap=\"hold\">\
+            <my-comp onChange=\"select\" data-id=\"1\" /></view>";
+        let output = compact(&convert("event-dataset", &[
+            ("index.axml", axml),
+            ("index.json", "{ \"usingComponents\": { \"my-comp\": \"./my-comp\" } }"),
+            ("index.js", "Component({ data: { id: 1 }, methods: { select(e) {}, hold() {} } });\n"),
+        ]));
+        assert!(output.contains("onClick={bindEvent(select, \"tap\", { dataset: { itemId: id, name: \"x\" } })}"));
+        assert!(output.contains(
+            "onContextMenu={bindEvent(hold, \"longTap\", { dataset: { itemId: id, name: \"x\" }, catch: true })}"
+        ));
+        // 自定义组件的回调不经过 bindEvent，直接传递方法
+        assert!(output.contains("<MyComp onChange={select} data-id=\"1\" />"), "{}", output);
+    }
+
+    #[test]
+    fn props_become_typed_interface_with_defaults() {
+        let script = "Component({\n  \
+            props: { title: '', count: 0, visible: true, list: [], onChange: () => {}, extra: null },\n  \
+            methods: { inc() { this.props.onChange(this.props.count + 1); } },\n});\n";
+        let output = compact(&convert("typed-props", &[
+            ("index.axml", "<view onTap=\"inc\">{{title}}</view>"),
+            ("index.js", script),
+        ]));
+        let interface = "export interface TypedPropsProps { title?: string; count?: number; visible?: boolean; \
+            list?: any[]; onChange?: (...args: any[]) => any; extra?: any; [key: string]: any; }";
+        assert!(output.contains(interface), "{}", output);
+        let signature = "export default function TypedProps({ title = '', count = 0, visible = true, \
+            list = [], onChange = () => {}, extra = null, ...props }: TypedPropsProps) {";
+        assert!(output.contains(signature), "{}", output);
+        assert!(output.contains("function inc() { onChange(count + 1); }"));
+    }
+
+    #[test]
+    fn lifecycles_become_effects() {
+        let script = "Component({\n  props: { count: 0 },\n  \
+            didMount() { console.log(this.props.count); },\n  \
+            didUpdate(prevProps, prevData) { if (prevProps.count !== this.props.count) this.inc(); },\n  \
+            didUnmount() { clearTimeout(this.timer); },\n  \
+            deriveDataFromProps(nextProps) { this.setData({ total: nextProps.count * 2 }); },\n  \
+            methods: { inc() {} },\n});\n";
+        let output = compact(&convert("lifecycles", &[("index.axml", "<view>{{total}}</view>"), ("index.js", script)]));
+        assert!(output.contains("const allProps = { ...props, count };"));
+        assert!(output.contains("function didUnmount() { clearTimeout(instance.timer); }"));
+        assert!(output.contains("function deriveDataFromProps(nextProps) { setTotal(nextProps.count * 2); }"));
+        let effects = "useEffect(() => { didMount(); return () => { didUnmount(); }; }, []);";
+        assert!(output.contains(effects), "{}", output);
+        assert!(output.contains("useDidUpdate(allProps, data, didUpdate);"));
+        assert!(output.contains("useDerivedData(allProps, deriveDataFromProps);"));
+    }
+
+    #[test]
+    fn page_lifecycles_register_page_hooks_in_order() {
+        let script = "Page({\n  data: { n: 0 },\n  onShow() {},\n  \
+            onLoad(query) { this.setData({ n: Number(query.n) }); },\n  \
+            onPullDownRefresh() {},\n  onUnload() {},\n});\n";
+        let output = convert("page-lifecycles", &[("index.axml", "<view>{{n}}</view>"), ("index.js", script)]);
+        assert!(output.contains("import { useLoad, usePullDownRefresh, useShow, useUnload } from \"../runtime\";\n"));
+        assert!(output.contains("function onLoad(query) { setN(Number(query.n)); }"));
+        let hooks = "useLoad(onLoad); useShow(onShow); useUnload(onUnload); usePullDownRefresh(onPullDownRefresh);";
+        assert!(compact(&output).contains(hooks), "{}", output);
+    }
+
+    #[test]
+    fn get_app_becomes_use_app() {
+        let script = "Page({\n  onLoad() { getApp().login('x'); },\n});\n";
+        let output = convert("get-app", &[("index.axml", "<view />"), ("index.js", script)]);
+        assert!(output.contains("import { useApp, useLoad } from \"../runtime\";\n"));
+        assert!(output.contains("  const appInstance = useApp();\n"));
+        assert!(output.contains("function onLoad() { appInstance.login('x'); }"));
+    }
+
+    #[test]
+    fn mixins_merge_with_component_precedence() {
+        let mixin = "export default {\n  data: { logs: [], count: 1 },\n  props: { level: 'info' },\n  \
+            didMount() { this.log('mounted'); },\n  \
+            methods: { log(msg) { this.setData({ logs: [...this.data.logs, msg] }); }, reset() {} },\n};\n";
+        let script = "import logger from './mixins/logger';\nComponent({\n  mixins: [logger],\n  data: { count: 2 },\n  \
+            didMount() { this.reset(); },\n  methods: { reset() { this.setData({ count: 0 }); } },\n});\n";
+        let output = compact(&convert("mixins", &[
+            ("index.axml", "<view>{{count}} {{logs.length}} {{level}}</view>"),
+            ("index.js", script),
+            ("mixins/logger.js", mixin),
+        ]));
+        assert!(!output.contains("import logger"));
+        assert!(output.contains("export default function Mixins({ level = 'info', ...props }: MixinsProps) {"));
+        // 组件自身的 data 和方法优先，mixin 的生命周期加上 mixin 名前缀后先执行
+        assert!(output.contains("const [count, setCount] = useState(2); const [logs, setLogs] = useState<any>([]);"));
+        assert!(output.contains("function reset() { setCount(0); } function log(msg) { setLogs([...logs, msg]); }"));
+        assert!(output.contains("function loggerDidMount() { log('mounted'); }"));
+        assert!(output.contains("useEffect(() => { loggerDidMount(); didMount(); }, []);"), "{}", output);
+    }
+
+    #[test]
+    fn observers_depend_on_observed_fields() {
+        let script = "Component({\n  props: { size: 0 },\n  data: { a: 1, b: 2, user: { name: '' } },\n  \
+            observers: {\n    \
+            'a, b': function (a, b) { this.setData({ sum: a + b }); },\n    \
+            'user.name': function (name) {},\n    'size': function (size) {},\n    '**': function () {},\n  },\n});\n";
+        let output = convert("observers", &[("index.axml", "<view>{{sum}}</view>"), ("index.js", script)]);
+        assert!(output.contains("function observeAB(a, b) { setSum(a + b); }"));
+        let observers = "useObserver(() => observeAB(a, b), [a, b]); \
+            useObserver(() => observeUserName(user?.name), [user?.name]); \
+            useObserver(() => observeSize(size), [size]); \
+            useObserver(() => observeAll(data), [a, b, user, sum, size]);";
+        assert!(compact(&output).contains(observers), "{}", output);
+    }
+
+    #[test]
+    fn refs_and_select_component_use_runtime() {
+        let script = "Component({\n  didMount() {\n    const child = this.$selectComponent('#child');\n    \
+            my.createSelectorQuery().select('#box').boundingClientRect().exec(() => {});\n  },\n  \
+            methods: { saveRef(ref) { this.list = ref; }, saveBox(ref) { this.box = ref; } },\n});\n";
+        let output = compact(&convert("refs", &[
+            ("index.axml", "<view id=\"box\" ref=\"saveBox\"><my-list id=\"child\" ref=\"saveRef\" /></view>"),
+            ("index.json", "{ \"usingComponents\": { \"my-list\": \"./my-list\" } }"),
+            ("index.js", script),
+        ]));
+        let runtime_import = "import { createSelectorQuery, selectComponent, useComponentInstance } from \"../runtime\";";
+        assert!(output.contains(runtime_import));
+        assert!(output.contains("const child = selectComponent('#child');"));
+        assert!(output.contains("createSelectorQuery().select('#box').boundingClientRect().exec(() => {});"));
+        assert!(output.contains("function saveRef(ref) { instance.list = ref; }"));
+        let jsx = "<div id=\"box\" ref={saveBox} > <MyList id=\"child\" componentRef={saveRef} /> </div>";
+        assert!(output.contains(jsx), "{}", output);
+    }
+
+    #[test]
+    fn css_modules_map_class_names_to_styles() {
+        let axml = "<view class=\"card title-text global ext\"><view class=\"card\" />\
+            <view class=\"card {{active ? 'on' : ''}}\" /></view>";
+        let dir = write_files("css-modules", &[
+            ("index.axml", axml),
+            ("index.acss", ":host { display: block; }\n.card { color: red; }\n.title-text { margin: 0; }\n"),
+            ("index.js", "Component({\n  externalClasses: ['ext'],\n  data: { active: false },\n});\n"),
+        ]);
+        let options = StyleOptions { css_modules: true, ..StyleOptions::default() };
+        let output = compact(&convert_axml_to_jsx(&dir.join("index.axml"), "../runtime", &options));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(output.contains("import styles from \"./index.module.css\";"));
+        assert!(output.contains("<div className={styles.host}>"));
+        let mixed = "<div className={`${styles.card} ${styles[\"title-text\"]} global ${props.ext ?? \"\"}`} >";
+        assert!(output.contains(mixed), "{}", output);
+        assert!(output.contains("<div className={styles.card} />"));
+        let dynamic = "<div className={scopedClass(styles, cx(\"card\", active ? 'on' : ''), \
+            { \"ext\": props.ext })} />";
+        assert!(output.contains(dynamic), "{}", output);
+    }
+
+    #[test]
+    fn inline_styles_become_style_objects() {
+        let axml = "<view style=\"font-size: 28rpx; -webkit-line-clamp: 2; --main-color: red; width: {{w}}px\" />\
+            <view style=\"{{dynamic}}\" /><view style=\"color: {{c}} !important\" />";
+        let output = compact(&convert("inline-styles", &[
+            ("index.axml", axml),
+            ("index.js", "Component({ data: { w: 1, dynamic: '', c: 'red' } });\n"),
+        ]));
+        let declarations = "<div style={{ fontSize: \"3.733333vw\", WebkitLineClamp: \"2\", \
+            \"--main-color\": \"red\", width: `${w}px` } as React.CSSProperties} />";
+        assert!(output.contains(declarations), "{}", output);
+        // 整个 style 是表达式时由运行时解析，!important 被丢弃
+        assert!(output.contains("<div style={styleObject(dynamic, \"0.133333vw\")} /> <div style={{ color: c }} />"));
+    }
+
+    #[test]
+    fn dynamic_class_lists_use_cx() {
+        let axml = "<view class=\"  btn   primary \" /><view class=\"btn {{active ? 'on' : ''}} {{size}}\" />\
+            <view class=\"icon-{{name}} big\" />";
+        let output = compact(&convert("class-builder", &[
+            ("index.axml", axml),
+            ("index.js", "Component({ data: { active: false, size: 'm', name: 'a' } });\n"),
+        ]));
+        assert!(output.contains("import { cx } from \"../runtime\";"));
+        let jsx = "<div className=\"btn primary\" /> <div className={cx(\"btn\", active ? 'on' : '', size)} /> \
+            <div className={cx(`icon-${name}`, \"big\")} />";
+        assert!(output.contains(jsx), "{}", output);
+    }
+
+    #[test]
+    fn include_targets_are_not_converted() {
+        let dir = write_files("include-only", &[
+            ("index.axml", "<import src=\"./tpl/a\" /><include src=\"./tpl/b.axml\" />"),
+            ("tpl/a.axml", "<template name=\"a\"><view>{{a}}</view></template>"),
+            ("tpl/b.axml", "<view>{{name}}</view>"),
+        ]);
+        let mut deps = vec![];
+        collect_all_dependencies(&dir.join("index.axml"), &mut HashSet::new(), &mut deps);
+        let out = dir.join("out");
+        for dep in &deps {
+            match dep {
+                DependencyType::Include(path) => copy_include(path, &dir, &out),
+                DependencyType::Template(path) => copy_dependency(path, &dir, &out),
+                other => panic!("unexpected dependency {:?}", other),
+            }
+        }
+        assert!(out.join("tpl/a.tsx").exists());
+        assert!(out.join("tpl/b.axml").exists());
+        assert!(!out.join("tpl/b.tsx").exists());
+        let output = convert_axml_to_jsx(&dir.join("index.axml"), "./runtime", &StyleOptions::default());
+        assert!(output.contains("{name}"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn conditional_chains_become_ternaries() {
+        let output = convert("conditional", &[
+            (
+                "index.axml",
+                "<view a:if=\"{{a > 1}}\">one</view>\n<view a:elif=\"{{b}}\">two</view>\n<view a:else>three</view>",
+            ),
+            ("index.js", "Component({ data: { a: 1, b: 2 } });\n"),
+        ]);
+        let expected = "{a > 1 ? ( <div > one </div> ) : b ? ( <div > two </div> ) : ( <div > three </div> )}";
+        assert!(compact(&output).contains(expected), "{}", output);
+    }
+
+    #[test]
+    fn conditional_without_else_renders_null() {
+        let output = convert("conditional-if", &[
+            ("index.axml", "<text a:if=\"{{count}}\">{{count}}</text>"),
+            ("index.js", "Component({ data: { count: 0 } });\n"),
+        ]);
+        assert!(compact(&output).contains("{count ? ( <span > {count} </span> ) : null}"));
+        assert!(!output.contains("&&"));
+    }
+
+    #[test]
+    fn orphan_else_is_rendered_unconditionally() {
+        let output = convert("conditional-orphan", &[("index.axml", "<view>a</view>\n<view a:else>b</view>")]);
+        assert!(compact(&output).contains("<div > a </div> <div > b </div>"), "{}", output);
+        assert!(!output.contains('?'));
+    }
+
+    #[test]
+    fn loops_map_with_keys() {
+        let axml = "<view a:for=\"{{list}}\" a:key=\"id\">{{index}}: {{item.name}}</view>\n\
+            <text a:for=\"{{tags}}\" a:for-item=\"tag\" a:key=\"*this\" a:if=\"{{tag}}\">{{tag}}</text>";
+        let output = compact(&convert("loop", &[
+            ("index.axml", axml),
+            ("index.js", "Component({ data: { list: [], tags: [] } });\n"),
+        ]));
+        assert!(output.contains("{list.map((item, index) => ( <div key={item.id} > {index}: {item.name} </div> ))}"));
+        assert!(output.contains("{tags.map((tag, index) => tag ? ( <span key={tag} > {tag} </span> ) : null)}"));
+    }
+
+    #[test]
+    fn nested_loops_use_their_own_item_and_index() {
+        let axml = "<block a:for=\"{{groups}}\" a:for-item=\"group\" a:for-index=\"g\">\
+            <view a:for=\"{{group.items}}\" a:key=\"{{item.id + g}}\">{{item.id}}</view></block>";
+        let output = compact(&convert("nested-loop", &[
+            ("index.axml", axml),
+            ("index.js", "Component({ data: { groups: [] } });\n"),
+        ]));
+        let expected = "{groups.map((group, g) => ( <React.Fragment key={g}> \
+            {group.items.map((item, index) => ( <div key={item.id + g} > {item.id} </div> ))} </React.Fragment> ))}";
+        assert!(output.contains(expected), "{}", output);
+    }
+
+    #[test]
+    fn templates_become_components_with_handlers_in_data() {
+        let axml = "<template name=\"card\"><view onTap=\"go\">{{title}}</view></template>\n\
+            <template is=\"card\" data=\"{{...item, title: name}}\" />\n\
+            <template is=\"{{name}}\" data=\"{{title: 'x'}}\" />";
+        let output = compact(&convert("template", &[
+            ("index.axml", axml),
+            ("index.js", "Component({ data: { item: {}, name: 'card' }, methods: { go() {} } });\n"),
+        ]));
+        let definition = "export function CardTemplate(data: any) { return ( <> \
+            <div onClick={bindEvent(data.go, \"tap\")} > {data.title} </div> </> ); }";
+        assert!(output.contains(definition), "{}", output);
+        assert!(output.contains("React.ComponentType<any>> = { \"card\": CardTemplate, };"));
+        assert!(output.contains("<CardTemplate {...{...item, title: name}} go={go} />"));
+        assert!(output.contains("{renderTemplate(name, { ...{title: 'x'}, go })}"));
+    }
+
+    #[test]
+    fn template_only_files_have_no_default_export() {
+        let axml = "<template name=\"row\"><view>{{text}}</view></template>";
+        let output = convert("template-only", &[("index.axml", axml)]);
+        assert!(output.contains("export function RowTemplate(data: any)"));
+        assert!(output.contains("{data.text}"));
+        assert!(!output.contains("export default"));
+    }
+
+    #[test]
+    fn state_with_the_name_of_a_prop_is_not_destructured() {
+        let script = "Component({\n  props: { list: [], title: '' },\n  methods: {\n    tap() {\n      \
+            this.setData({ 'list[0].x': 1 });\n      console.log(this.props.list);\n    },\n  },\n});\n";
+        let output = convert("shadowed-prop", &[
+            ("index.axml", "<view onTap=\"tap\">{{list.length}}{{props.list.length}}{{title}}</view>"),
+            ("index.js", script),
+        ]);
+        assert!(output.contains("export default function ShadowedProp({ title = '', ...props }: ShadowedPropProps)"));
+        assert!(output.contains("const [list, setList] = useState<any>(props.list ?? []);"));
+        assert!(output.contains("console.log(props.list);"));
+        assert!(output.contains("{list.length}{props.list.length}{title}"));
+    }
+}
@@ -101,7 +101,7 @@ pub fn build_from_root(
     }
     visited.insert(file.clone());
 
-    graph.entry(file.clone()).or_insert_with(Vec::new);
+    graph.entry(file.clone()).or_default();
 
     let code = fs::read_to_string(&file).unwrap_or_default();
     let imports = extract_imports(&code);
@@ -0,0 +1,28 @@
+//! 生成代码依赖的 React 运行时，转换时写入目标目录的 `runtime/` 下
+use std::{fs, io, path::Path};
+
+pub const RUNTIME_DIR: &str = "runtime";
+
+const FILES: [(&str, &str); 10] = [
+    ("index.ts", include_str!("runtime/index.ts")),
+    ("context.ts", include_str!("runtime/context.ts")),
+    ("data.ts", include_str!("runtime/data.ts")),
+    ("events.ts", include_str!("runtime/events.ts")),
+    ("lifecycle.ts", include_str!("runtime/lifecycle.ts")),
+    ("page.ts", include_str!("runtime/page.ts")),
+    ("selector.ts", include_str!("runtime/selector.ts")),
+    ("styles.ts", include_str!("runtime/styles.ts")),
+    ("components.tsx", include_str!("runtime/components.tsx")),
+    // 依赖 react-router-dom，只由生成的 App.tsx 引用，不从 index 导出
+    ("app.tsx", include_str!("runtime/app.tsx")),
+];
+
+/// 写入运行时文件
+pub fn write_runtime(target_root: &Path) -> io::Result<()> {
+    let dir = target_root.join(RUNTIME_DIR);
+    fs::create_dir_all(&dir)?;
+    for (name, content) in FILES {
+        fs::write(dir.join(name), content)?;
+    }
+    Ok(())
+}
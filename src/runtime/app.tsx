@@ -0,0 +1,98 @@
+// 应用外壳：窗口配置与 tabBar，由 mini2react 生成，请勿手动修改
+import React, { useEffect } from "react";
+import { useLocation, useNavigate } from "react-router-dom";
+
+/** app.json 和页面 json 中的 window 配置 */
+export interface WindowConfig {
+  defaultTitle?: string;
+  titleBarColor?: string;
+  backgroundColor?: string;
+  transparentTitle?: string;
+  [key: string]: any;
+}
+
+export interface TabBarItem {
+  pagePath: string;
+  name?: string;
+  icon?: string;
+  activeIcon?: string;
+}
+
+export interface TabBarConfig {
+  textColor?: string;
+  selectedColor?: string;
+  backgroundColor?: string;
+  items: TabBarItem[];
+}
+
+export interface PageRoute {
+  path: string;
+  component: React.ComponentType<any>;
+  window?: WindowConfig;
+}
+
+// 路由路径统一以 / 开头，便于与 tabBar 的 pagePath 比较
+function normalize(path: string) {
+  return path.startsWith("/") ? path : `/${path}`;
+}
+
+export function TabBar({ config }: { config: TabBarConfig }) {
+  const { pathname } = useLocation();
+  const navigate = useNavigate();
+  return (
+    <nav className="a-tab-bar" style={{ display: "flex", backgroundColor: config.backgroundColor }}>
+      {config.items.map((item) => {
+        const path = normalize(item.pagePath);
+        const active = pathname === path;
+        const icon = active ? item.activeIcon ?? item.icon : item.icon;
+        return (
+          <div
+            key={path}
+            className="a-tab-bar-item"
+            style={{ flex: 1, textAlign: "center", color: active ? config.selectedColor : config.textColor }}
+            onClick={() => navigate(path, { replace: true })}
+          >
+            {icon && <img src={icon} alt="" style={{ width: 24, height: 24 }} />}
+            <div>{item.name}</div>
+          </div>
+        );
+      })}
+    </nav>
+  );
+}
+
+/** 应用窗口配置并在 tab 页面底部显示 tabBar，页面配置覆盖全局配置 */
+export function AppLayout({
+  window: appWindow = {},
+  pages,
+  tabBar,
+  children,
+}: {
+  window?: WindowConfig;
+  pages: PageRoute[];
+  tabBar?: TabBarConfig;
+  children?: React.ReactNode;
+}) {
+  const { pathname } = useLocation();
+  const page = pages.find((route) => route.path === pathname);
+  const config = { ...appWindow, ...page?.window };
+  const isTab = tabBar?.items.some((item) => normalize(item.pagePath) === pathname);
+
+  useEffect(() => {
+    if (config.defaultTitle !== undefined) document.title = config.defaultTitle;
+  }, [config.defaultTitle]);
+
+  return (
+    <div className="a-app" style={{ minHeight: "100vh", display: "flex", flexDirection: "column", backgroundColor: config.backgroundColor }}>
+      {config.transparentTitle !== "always" && (
+        <header className="a-title-bar" style={{ textAlign: "center", padding: "12px 0", backgroundColor: config.titleBarColor }}>
+          {config.defaultTitle}
+        </header>
+      )}
+      <main className="a-page" style={{ flex: 1 }}>
+        {children}
+      </main>
+      {tabBar && isTab && <TabBar config={tabBar} />}
+    </div>
+  );
+}
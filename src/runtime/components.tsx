@@ -0,0 +1,565 @@
+// 小程序内置组件的 React 实现，由 mini2react 生成，请勿手动修改
+import React, { createContext, forwardRef, useCallback, useContext, useEffect, useId, useRef, useState } from "react";
+
+type Handler = ((event: any) => void) | undefined;
+
+interface BaseProps {
+  id?: string;
+  className?: string;
+  style?: React.CSSProperties;
+  hidden?: boolean;
+  children?: React.ReactNode;
+  [key: string]: any;
+}
+
+// 以小程序事件对象的形状调用事件回调，currentTarget.dataset 由 bindEvent 补全
+function emit(handler: Handler, type: string, detail: any = {}) {
+  handler?.({
+    type,
+    timeStamp: Date.now(),
+    detail,
+    target: { dataset: {} },
+    currentTarget: { dataset: {} },
+    stopPropagation() {},
+  });
+}
+
+// 合并调用方传入的 ref 与组件内部使用的 ref
+function useMergedRef<T>(outer: React.ForwardedRef<T>, inner: React.MutableRefObject<T | null>) {
+  return useCallback(
+    (node: T | null) => {
+      inner.current = node;
+      if (typeof outer === "function") outer(node);
+      else if (outer) outer.current = node;
+    },
+    [outer, inner],
+  );
+}
+
+function classNames(...names: Array<string | false | undefined>) {
+  return names.filter(Boolean).join(" ");
+}
+
+export const ScrollView = forwardRef<any, BaseProps>(function ScrollView({
+  scrollX,
+  scrollY,
+  scrollTop,
+  scrollLeft,
+  scrollIntoView,
+  scrollWithAnimation,
+  upperThreshold = 50,
+  lowerThreshold = 50,
+  onScroll,
+  onScrollToUpper,
+  onScrollToLower,
+  className,
+  style,
+  children,
+  ...rest
+}: BaseProps, ref) {
+  const node = useRef<HTMLDivElement | null>(null);
+  const mergedRef = useMergedRef(ref, node);
+  const edge = useRef({ upper: false, lower: false });
+  const behavior = scrollWithAnimation ? "smooth" : "auto";
+
+  useEffect(() => {
+    if (node.current && scrollTop !== undefined) node.current.scrollTo({ top: Number(scrollTop), behavior });
+  }, [scrollTop]);
+  useEffect(() => {
+    if (node.current && scrollLeft !== undefined) node.current.scrollTo({ left: Number(scrollLeft), behavior });
+  }, [scrollLeft]);
+  useEffect(() => {
+    if (scrollIntoView) node.current?.querySelector(`#${scrollIntoView}`)?.scrollIntoView({ behavior });
+  }, [scrollIntoView]);
+
+  const handleScroll = () => {
+    const el = node.current;
+    if (!el) return;
+    emit(onScroll, "scroll", {
+      scrollTop: el.scrollTop,
+      scrollLeft: el.scrollLeft,
+      scrollHeight: el.scrollHeight,
+      scrollWidth: el.scrollWidth,
+    });
+    const offset = scrollY ? el.scrollTop : el.scrollLeft;
+    const remain = scrollY
+      ? el.scrollHeight - el.clientHeight - el.scrollTop
+      : el.scrollWidth - el.clientWidth - el.scrollLeft;
+    const upper = offset <= upperThreshold;
+    const lower = remain <= lowerThreshold;
+    if (upper && !edge.current.upper) emit(onScrollToUpper, "scrollToUpper");
+    if (lower && !edge.current.lower) emit(onScrollToLower, "scrollToLower");
+    edge.current = { upper, lower };
+  };
+
+  return (
+    <div
+      {...rest}
+      ref={mergedRef}
+      className={classNames("a-scroll-view", className)}
+      style={{ overflowX: scrollX ? "auto" : "hidden", overflowY: scrollY ? "auto" : "hidden", ...style }}
+      onScroll={handleScroll}
+    >
+      {children}
+    </div>
+  );
+});
+
+export const Swiper = forwardRef<any, BaseProps>(function Swiper({
+  indicatorDots,
+  indicatorColor = "rgba(0, 0, 0, .3)",
+  indicatorActiveColor = "#000",
+  autoplay,
+  current = 0,
+  interval = 5000,
+  duration = 500,
+  circular,
+  vertical,
+  onChange,
+  className,
+  style,
+  children,
+  ...rest
+}: BaseProps, ref) {
+  const items = React.Children.toArray(children);
+  const [index, setIndex] = useState(Number(current));
+  useEffect(() => setIndex(Number(current)), [current]);
+
+  const goTo = (next: number, source: string) => {
+    const count = items.length;
+    if (count === 0) return;
+    const target = circular ? (next + count) % count : Math.max(0, Math.min(count - 1, next));
+    setIndex(target);
+    emit(onChange, "change", { current: target, source });
+  };
+
+  useEffect(() => {
+    if (!autoplay) return;
+    const timer = setInterval(() => goTo(index + 1, "autoplay"), Number(interval));
+    return () => clearInterval(timer);
+  }, [autoplay, interval, index, items.length]);
+
+  const touchStart = useRef(0);
+  const axis = vertical ? "clientY" : "clientX";
+
+  return (
+    <div {...rest} ref={ref} className={classNames("a-swiper", className)} style={{ position: "relative", overflow: "hidden", ...style }}>
+      <div
+        style={{
+          display: "flex",
+          flexDirection: vertical ? "column" : "row",
+          height: "100%",
+          transition: `transform ${duration}ms`,
+          transform: vertical ? `translateY(-${index * 100}%)` : `translateX(-${index * 100}%)`,
+        }}
+        onTouchStart={(e) => (touchStart.current = e.touches[0][axis])}
+        onTouchEnd={(e) => {
+          const delta = e.changedTouches[0][axis] - touchStart.current;
+          if (Math.abs(delta) > 30) goTo(index + (delta < 0 ? 1 : -1), "touch");
+        }}
+      >
+        {items}
+      </div>
+      {indicatorDots && (
+        <div className="a-swiper-dots" style={{ position: "absolute", bottom: 10, width: "100%", textAlign: "center" }}>
+          {items.map((_, i) => (
+            <span
+              key={i}
+              style={{
+                display: "inline-block",
+                width: 8,
+                height: 8,
+                margin: "0 4px",
+                borderRadius: "50%",
+                background: i === index ? indicatorActiveColor : indicatorColor,
+              }}
+            />
+          ))}
+        </div>
+      )}
+    </div>
+  );
+});
+
+export const SwiperItem = forwardRef<any, BaseProps>(function SwiperItem({ className, style, children, ...rest }: BaseProps, ref) {
+  return (
+    <div {...rest} ref={ref} className={classNames("a-swiper-item", className)} style={{ flex: "0 0 100%", ...style }}>
+      {children}
+    </div>
+  );
+});
+
+const ICONS: Record<string, string> = {
+  success: "✔",
+  success_no_circle: "✓",
+  info: "ℹ",
+  warn: "!",
+  waiting: "◷",
+  clear: "✕",
+  cancel: "✕",
+  download: "↓",
+  search: "⌕",
+  loading: "↻",
+};
+
+export const Icon = forwardRef<any, BaseProps>(function Icon({ type, size = 23, color, className, style, ...rest }: BaseProps, ref) {
+  return (
+    <span
+      {...rest}
+      ref={ref}
+      className={classNames("a-icon", `a-icon-${type}`, className)}
+      style={{ display: "inline-block", fontSize: Number(size), lineHeight: 1, color, ...style }}
+    >
+      {ICONS[type] ?? ""}
+    </span>
+  );
+});
+
+export const Progress = forwardRef<any, BaseProps>(function Progress({
+  percent = 0,
+  showInfo,
+  strokeWidth = 6,
+  activeColor = "#108ee9",
+  backgroundColor = "#ddd",
+  className,
+  style,
+  ...rest
+}: BaseProps, ref) {
+  const value = Math.max(0, Math.min(100, Number(percent)));
+  return (
+    <div {...rest} ref={ref} className={classNames("a-progress", className)} style={{ display: "flex", alignItems: "center", ...style }}>
+      <div style={{ flex: 1, height: Number(strokeWidth), background: backgroundColor }}>
+        <div style={{ width: `${value}%`, height: "100%", background: activeColor }} />
+      </div>
+      {showInfo && <span style={{ marginLeft: 8 }}>{value}%</span>}
+    </div>
+  );
+});
+
+function renderRichNodes(nodes: any[]): React.ReactNode {
+  return nodes.map((node, i) => {
+    if (node.type === "text") return node.text;
+    const { class: className, style, ...attrs } = node.attrs ?? {};
+    return React.createElement(
+      node.name,
+      { key: i, ...attrs, className, style: typeof style === "string" ? undefined : style },
+      node.children ? renderRichNodes(node.children) : undefined,
+    );
+  });
+}
+
+export const RichText = forwardRef<any, BaseProps>(function RichText({ nodes, className, ...rest }: BaseProps, ref) {
+  if (typeof nodes === "string") {
+    return <div {...rest} ref={ref} className={classNames("a-rich-text", className)} dangerouslySetInnerHTML={{ __html: nodes }} />;
+  }
+  return (
+    <div {...rest} ref={ref} className={classNames("a-rich-text", className)}>
+      {renderRichNodes(nodes ?? [])}
+    </div>
+  );
+});
+
+export const Button = forwardRef<any, BaseProps>(function Button({ type = "default", size, plain, loading, disabled, formType, openType, className, children, ...rest }: BaseProps, ref) {
+  return (
+    <button
+      {...rest}
+      ref={ref}
+      type={formType === "submit" || formType === "reset" ? formType : "button"}
+      disabled={disabled || loading}
+      className={classNames("a-button", `a-button-${type}`, size === "mini" && "a-button-mini", plain && "a-button-plain", className)}
+    >
+      {children}
+    </button>
+  );
+});
+
+export const Form = forwardRef<any, BaseProps>(function Form({ onSubmit, onReset, className, children, ...rest }: BaseProps, ref) {
+  return (
+    <form
+      {...rest}
+      ref={ref}
+      className={classNames("a-form", className)}
+      onSubmit={(e) => {
+        e.preventDefault();
+        const value: Record<string, any> = {};
+        new FormData(e.currentTarget).forEach((v, k) => {
+          value[k] = k in value ? [].concat(value[k], v as any) : v;
+        });
+        emit(onSubmit, "submit", { value });
+      }}
+      onReset={() => emit(onReset, "reset")}
+    >
+      {children}
+    </form>
+  );
+});
+
+function useSyncedValue(value: any) {
+  const [current, setCurrent] = useState(value ?? "");
+  useEffect(() => setCurrent(value ?? ""), [value]);
+  return [current, setCurrent] as const;
+}
+
+export const Input = forwardRef<any, BaseProps>(function Input({
+  value,
+  type = "text",
+  password,
+  maxlength = 140,
+  focus,
+  confirmType,
+  placeholderClass,
+  placeholderStyle,
+  onInput,
+  onConfirm,
+  onFocus,
+  onBlur,
+  className,
+  ...rest
+}: BaseProps, ref) {
+  const [current, setCurrent] = useSyncedValue(value);
+  const inputType = password ? "password" : type === "number" || type === "digit" ? "number" : "text";
+  return (
+    <input
+      {...rest}
+      ref={ref}
+      className={classNames("a-input", className)}
+      type={inputType}
+      value={current}
+      maxLength={Number(maxlength) < 0 ? undefined : Number(maxlength)}
+      autoFocus={focus}
+      enterKeyHint={confirmType}
+      inputMode={type === "digit" ? "decimal" : type === "number" || type === "idcard" ? "numeric" : undefined}
+      onChange={(e) => {
+        setCurrent(e.target.value);
+        emit(onInput, "input", { value: e.target.value });
+      }}
+      onKeyDown={(e) => e.key === "Enter" && emit(onConfirm, "confirm", { value: current })}
+      onFocus={() => emit(onFocus, "focus", { value: current })}
+      onBlur={() => emit(onBlur, "blur", { value: current })}
+    />
+  );
+});
+
+export const Textarea = forwardRef<any, BaseProps>(function Textarea({
+  value,
+  maxlength = 140,
+  focus,
+  autoHeight,
+  showCount,
+  onInput,
+  onConfirm,
+  onFocus,
+  onBlur,
+  className,
+  style,
+  ...rest
+}: BaseProps, ref) {
+  const [current, setCurrent] = useSyncedValue(value);
+  const node = useRef<HTMLTextAreaElement | null>(null);
+  const mergedRef = useMergedRef(ref, node);
+  useEffect(() => {
+    if (autoHeight && node.current) {
+      node.current.style.height = "auto";
+      node.current.style.height = `${node.current.scrollHeight}px`;
+    }
+  }, [autoHeight, current]);
+  return (
+    <div className={classNames("a-textarea", className)} style={style}>
+      <textarea
+        {...rest}
+        ref={mergedRef}
+        value={current}
+        maxLength={Number(maxlength) < 0 ? undefined : Number(maxlength)}
+        autoFocus={focus}
+        onChange={(e) => {
+          setCurrent(e.target.value);
+          emit(onInput, "input", { value: e.target.value });
+        }}
+        onKeyDown={(e) => e.key === "Enter" && !e.shiftKey && emit(onConfirm, "confirm", { value: current })}
+        onFocus={() => emit(onFocus, "focus", { value: current })}
+        onBlur={() => emit(onBlur, "blur", { value: current })}
+      />
+      {showCount && <span className="a-textarea-count">{String(current).length}</span>}
+    </div>
+  );
+});
+
+// 选择组通过原生 change 事件冒泡收集选中值
+export const CheckboxGroup = forwardRef<any, BaseProps>(function CheckboxGroup({ name, onChange, className, children, ...rest }: BaseProps, ref) {
+  return (
+    <div
+      {...rest}
+      ref={ref}
+      className={classNames("a-checkbox-group", className)}
+      onChange={(e) => {
+        const inputs = e.currentTarget.querySelectorAll<HTMLInputElement>("input[type=checkbox]:checked");
+        emit(onChange, "change", { value: Array.from(inputs).map((input) => input.value) });
+      }}
+    >
+      {children}
+    </div>
+  );
+});
+
+export const Checkbox = forwardRef<any, BaseProps>(function Checkbox({ value, checked, disabled, color, onChange, className, style, ...rest }: BaseProps, ref) {
+  return (
+    <input
+      {...rest}
+      ref={ref}
+      type="checkbox"
+      className={classNames("a-checkbox", className)}
+      style={{ accentColor: color, ...style }}
+      value={value}
+      defaultChecked={checked}
+      disabled={disabled}
+      onChange={(e) => emit(onChange, "change", { value: e.target.checked })}
+    />
+  );
+});
+
+const RadioGroupContext = createContext<string | undefined>(undefined);
+
+export const RadioGroup = forwardRef<any, BaseProps>(function RadioGroup({ name, onChange, className, children, ...rest }: BaseProps, ref) {
+  const id = useId();
+  const groupName = name ?? id;
+  return (
+    <RadioGroupContext.Provider value={groupName}>
+      <div
+        {...rest}
+        ref={ref}
+        className={classNames("a-radio-group", className)}
+        onChange={(e) => emit(onChange, "change", { value: (e.target as HTMLInputElement).value })}
+      >
+        {children}
+      </div>
+    </RadioGroupContext.Provider>
+  );
+});
+
+export const Radio = forwardRef<any, BaseProps>(function Radio({ value, checked, disabled, color, className, style, ...rest }: BaseProps, ref) {
+  const name = useContext(RadioGroupContext);
+  return (
+    <input
+      {...rest}
+      ref={ref}
+      type="radio"
+      name={name}
+      className={classNames("a-radio", className)}
+      style={{ accentColor: color, ...style }}
+      value={value}
+      defaultChecked={checked}
+      disabled={disabled}
+    />
+  );
+});
+
+export const Switch = forwardRef<any, BaseProps>(function Switch({ checked, disabled, color, onChange, className, style, ...rest }: BaseProps, ref) {
+  return (
+    <input
+      {...rest}
+      ref={ref}
+      type="checkbox"
+      role="switch"
+      className={classNames("a-switch", className)}
+      style={{ accentColor: color, ...style }}
+      defaultChecked={checked}
+      disabled={disabled}
+      onChange={(e) => emit(onChange, "change", { value: e.target.checked })}
+    />
+  );
+});
+
+export const Slider = forwardRef<any, BaseProps>(function Slider({ min = 0, max = 100, step = 1, value, showValue, disabled, onChange, onChanging, className, ...rest }: BaseProps, ref) {
+  const [current, setCurrent] = useSyncedValue(value ?? min);
+  return (
+    <div className={classNames("a-slider", className)} style={{ display: "flex", alignItems: "center" }}>
+      <input
+        {...rest}
+        ref={ref}
+        type="range"
+        min={min}
+        max={max}
+        step={step}
+        value={current}
+        disabled={disabled}
+        onChange={(e) => {
+          setCurrent(Number(e.target.value));
+          emit(onChanging, "changing", { value: Number(e.target.value) });
+        }}
+        onPointerUp={() => emit(onChange, "change", { value: current })}
+      />
+      {showValue && <span style={{ marginLeft: 8 }}>{current}</span>}
+    </div>
+  );
+});
+
+// 用透明的原生 select 覆盖在子节点上实现选择器
+export const Picker = forwardRef<any, BaseProps>(function Picker({ range = [], rangeKey, value = 0, disabled, onChange, className, style, children, ...rest }: BaseProps, ref) {
+  return (
+    <label {...rest} ref={ref} className={classNames("a-picker", className)} style={{ position: "relative", display: "block", ...style }}>
+      {children}
+      <select
+        value={value}
+        disabled={disabled}
+        style={{ position: "absolute", inset: 0, opacity: 0, width: "100%" }}
+        onChange={(e) => emit(onChange, "change", { value: Number(e.target.value) })}
+      >
+        {range.map((item: any, i: number) => (
+          <option key={i} value={i}>
+            {rangeKey ? item[rangeKey] : item}
+          </option>
+        ))}
+      </select>
+    </label>
+  );
+});
+
+export const Navigator = forwardRef<any, BaseProps>(function Navigator({ url, openType = "navigate", delta = 1, hoverClass, className, children, ...rest }: BaseProps, ref) {
+  const handleClick = (e: React.MouseEvent) => {
+    e.preventDefault();
+    if (openType === "navigateBack") {
+      window.history.go(-Number(delta));
+    } else if (openType === "redirect" || openType === "switchTab" || openType === "reLaunch") {
+      window.location.replace(`#${url}`);
+    } else {
+      window.location.hash = url;
+    }
+  };
+  return (
+    <a {...rest} ref={ref} href={`#${url}`} className={classNames("a-navigator", className)} onClick={handleClick}>
+      {children}
+    </a>
+  );
+});
+
+const IMAGE_MODES: Record<string, React.CSSProperties> = {
+  scaleToFill: { objectFit: "fill" },
+  aspectFit: { objectFit: "contain" },
+  aspectFill: { objectFit: "cover" },
+  widthFix: { height: "auto" },
+  heightFix: { width: "auto" },
+  top: { objectFit: "none", objectPosition: "top" },
+  bottom: { objectFit: "none", objectPosition: "bottom" },
+  center: { objectFit: "none", objectPosition: "center" },
+  left: { objectFit: "none", objectPosition: "left" },
+  right: { objectFit: "none", objectPosition: "right" },
+  "top left": { objectFit: "none", objectPosition: "top left" },
+  "top right": { objectFit: "none", objectPosition: "top right" },
+  "bottom left": { objectFit: "none", objectPosition: "bottom left" },
+  "bottom right": { objectFit: "none", objectPosition: "bottom right" },
+};
+
+export const Image = forwardRef<any, BaseProps>(function Image({ src, mode = "scaleToFill", lazyLoad, onLoad, onError, className, style, ...rest }: BaseProps, ref) {
+  return (
+    <img
+      {...rest}
+      ref={ref}
+      src={src}
+      className={classNames("a-image", className)}
+      style={{ ...IMAGE_MODES[mode], ...style }}
+      loading={lazyLoad ? "lazy" : undefined}
+      onLoad={(e) => emit(onLoad, "load", { width: e.currentTarget.naturalWidth, height: e.currentTarget.naturalHeight })}
+      onError={() => emit(onError, "error", { errMsg: "image load failed" })}
+    />
+  );
+});
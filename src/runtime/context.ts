@@ -0,0 +1,92 @@
+// 应用实例上下文，对应小程序的 getApp()，由 mini2react 生成，请勿手动修改
+import { createContext, useContext, useEffect, useRef, useState } from "react";
+
+let current: Record<string, any> | null = null;
+
+export const AppContext = createContext<Record<string, any> | null>(null);
+
+/** 由生成的 AppProvider 在每次渲染时注册最新的应用实例 */
+export function registerApp(app: Record<string, any>) {
+  current = app;
+}
+
+// 模块顶层的 getApp() 在 AppProvider 渲染前执行，返回的代理在访问属性时才读取应用实例
+const appProxy = new Proxy({} as Record<string, any>, {
+  get: (_, key) => current?.[key as string],
+  set: (_, key, value) => {
+    if (current) current[key as string] = value;
+    return true;
+  },
+});
+
+// 对象被修改（包括嵌套字段）时调用 notify，同一对象返回同一个代理
+function reactive<T extends object>(target: T, notify: () => void, proxies = new WeakMap<object, any>()): T {
+  if (proxies.has(target)) return proxies.get(target);
+  const proxy = new Proxy(target, {
+    get: (obj, key, receiver) => {
+      const value = Reflect.get(obj, key, receiver);
+      return value && typeof value === "object" ? reactive(value, notify, proxies) : value;
+    },
+    set: (obj, key, value, receiver) => {
+      const changed = !Object.is(Reflect.get(obj, key, receiver), value);
+      const result = Reflect.set(obj, key, value, receiver);
+      if (changed) notify();
+      return result;
+    },
+    deleteProperty: (obj, key) => {
+      const result = Reflect.deleteProperty(obj, key);
+      notify();
+      return result;
+    },
+  });
+  proxies.set(target, proxy);
+  return proxy;
+}
+
+/**
+ * App 中对象类型的字段（如 globalData），与小程序一样可以直接修改，
+ * 修改后 AppProvider 重新渲染并提供新的应用实例，使用 useApp() 的组件随之更新
+ */
+export function useAppData<T extends object>(initial: T): T {
+  const [, setVersion] = useState(0);
+  const data = useRef<T | null>(null);
+  if (data.current === null) data.current = reactive(initial, () => setVersion((version) => version + 1));
+  return data.current;
+}
+
+/** 组件外部使用的 getApp()，不会随 globalData 更新重新渲染 */
+export function getApp() {
+  return appProxy;
+}
+
+/** 组件内部使用的 getApp()，globalData 等对象字段被修改时重新渲染，数字、字符串等其它字段的修改不会触发渲染 */
+export function useApp() {
+  return useContext(AppContext) ?? appProxy;
+}
+
+/** 对应 App 的 onLaunch(options)，应用挂载时调用一次 */
+export function useLaunch(onLaunch: ((options: { query: Record<string, string>; path: string }) => void) | undefined) {
+  const callback = useRef(onLaunch);
+  callback.current = onLaunch;
+  useEffect(() => {
+    const { hash } = window.location;
+    const [path, query = ""] = hash.replace(/^#/, "").split("?");
+    callback.current?.({ query: Object.fromEntries(new URLSearchParams(query)), path });
+  }, []);
+}
+
+/** 对应 App 的 onError(error)，捕获未处理的异常和 Promise 拒绝 */
+export function useAppError(onError: ((error: string) => void) | undefined) {
+  const callback = useRef(onError);
+  callback.current = onError;
+  useEffect(() => {
+    const error = (e: ErrorEvent) => callback.current?.(e.message);
+    const rejection = (e: PromiseRejectionEvent) => callback.current?.(String(e.reason));
+    window.addEventListener("error", error);
+    window.addEventListener("unhandledrejection", rejection);
+    return () => {
+      window.removeEventListener("error", error);
+      window.removeEventListener("unhandledrejection", rejection);
+    };
+  }, []);
+}
@@ -0,0 +1,35 @@
+// setData 路径更新，由 mini2react 生成，请勿手动修改
+
+type Path = Array<string | number>;
+
+/** 解析 `list[0].name` 形式的 setData 路径 */
+export function parsePath(key: string): Path {
+  const path: Path = [];
+  key.replace(/([^.[\]]+)|\[(\d+)\]/g, (_, name, index) => {
+    path.push(index !== undefined ? Number(index) : name);
+    return "";
+  });
+  return path;
+}
+
+/** 不修改原对象，返回在 path 处写入 value 后的新对象 */
+export function setIn(target: any, path: Path, value: any): any {
+  if (path.length === 0) return value;
+  const [key, ...rest] = path;
+  const copy: any = Array.isArray(target) ? [...target] : target != null ? { ...target } : typeof key === "number" ? [] : {};
+  copy[key] = setIn(target?.[key], rest, value);
+  return copy;
+}
+
+/** 按 setData 的语义把 patch 分发到各个状态的 setter */
+export function applyData(setters: Record<string, (value: any) => void>, patch: Record<string, any>) {
+  for (const [key, value] of Object.entries(patch)) {
+    const [root, ...path] = parsePath(key);
+    const setter = setters[root];
+    if (!setter) {
+      console.warn(`[mini2react] setData: \`${root}\` is not declared in data`);
+      continue;
+    }
+    setter(path.length ? (prev: any) => setIn(prev, path, value) : value);
+  }
+}
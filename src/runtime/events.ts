@@ -0,0 +1,64 @@
+// 小程序事件对象适配，由 mini2react 生成，请勿手动修改
+
+type Handler = ((event: any) => void) | undefined;
+
+interface BindOptions {
+  /** 元素上 data-* 属性组成的 dataset，key 已转为驼峰 */
+  dataset?: Record<string, any>;
+  /** catch 绑定，调用后阻止冒泡 */
+  catch?: boolean;
+}
+
+function domTarget(element: any) {
+  return {
+    id: element?.id ?? "",
+    dataset: element?.dataset ? { ...element.dataset } : {},
+  };
+}
+
+function touches(list: any) {
+  return Array.from(list ?? [], (touch: any) => ({
+    identifier: touch.identifier,
+    pageX: touch.pageX,
+    pageY: touch.pageY,
+    clientX: touch.clientX,
+    clientY: touch.clientY,
+  }));
+}
+
+function domDetail(event: any) {
+  const { nativeEvent, target } = event;
+  if (nativeEvent instanceof MouseEvent) return { x: event.pageX, y: event.pageY };
+  if (target && "value" in target) return { value: target.value };
+  return {};
+}
+
+// React 合成事件转换为小程序事件对象，运行时组件触发的事件只补全 currentTarget
+function toMiniEvent(event: any, type: string, dataset: Record<string, any>) {
+  if (!event?.nativeEvent) {
+    return { ...event, type: event?.type ?? type, currentTarget: { ...event?.currentTarget, dataset } };
+  }
+  return {
+    type,
+    timeStamp: event.timeStamp,
+    detail: domDetail(event),
+    target: domTarget(event.target),
+    currentTarget: { id: event.currentTarget?.id ?? "", dataset },
+    touches: touches(event.touches),
+    changedTouches: touches(event.changedTouches),
+    stopPropagation: () => event.stopPropagation(),
+    preventDefault: () => event.preventDefault(),
+    nativeEvent: event,
+  };
+}
+
+/** 包装事件回调，使回调收到的事件对象与小程序保持一致，多个回调（如 onTap 和 catchTap）按顺序调用 */
+export function bindEvent(handler: Handler | Handler[], type: string, options: BindOptions = {}) {
+  const { dataset = {} } = options;
+  const handlers = Array.isArray(handler) ? handler : [handler];
+  return (event: any) => {
+    if (options.catch) event?.stopPropagation?.();
+    const miniEvent = toMiniEvent(event, type, dataset);
+    handlers.forEach((handler) => handler?.(miniEvent));
+  };
+}
@@ -0,0 +1,9 @@
+// mini2react 运行时入口，由 mini2react 生成，请勿手动修改
+export * from "./components";
+export * from "./context";
+export * from "./data";
+export * from "./events";
+export * from "./lifecycle";
+export * from "./page";
+export * from "./selector";
+export * from "./styles";
@@ -0,0 +1,41 @@
+// 组件生命周期适配，由 mini2react 生成，请勿手动修改
+import { useEffect, useRef } from "react";
+
+function shallowEqual(a: Record<string, any>, b: Record<string, any>) {
+  const keys = Object.keys(a);
+  return keys.length === Object.keys(b).length && keys.every((key) => Object.is(a[key], b[key]));
+}
+
+/** 对应 didUpdate(prevProps, prevData)，首次渲染后不调用 */
+export function useDidUpdate(
+  props: Record<string, any>,
+  data: Record<string, any>,
+  didUpdate: (prevProps: Record<string, any>, prevData: Record<string, any>) => void,
+) {
+  const prev = useRef<{ props: Record<string, any>; data: Record<string, any> } | null>(null);
+  useEffect(() => {
+    const last = prev.current;
+    prev.current = { props, data };
+    if (last && (!shallowEqual(last.props, props) || !shallowEqual(last.data, data))) {
+      didUpdate(last.props, last.data);
+    }
+  });
+}
+
+/** 对应 deriveDataFromProps(nextProps)，在渲染阶段 props 变化时调用，其中的 setData 会在本次渲染后立即生效 */
+export function useDerivedData(props: Record<string, any>, derive: (nextProps: Record<string, any>) => void) {
+  const prev = useRef<Record<string, any> | null>(null);
+  if (!prev.current || !shallowEqual(prev.current, props)) {
+    prev.current = props;
+    derive(props);
+  }
+}
+
+/** 对应 observers，依赖的字段变化后调用，首次渲染后不调用；与小程序不同，setData 写入相同的值不会触发 */
+export function useObserver(observer: () => void, deps: any[]) {
+  const mounted = useRef(false);
+  useEffect(() => {
+    if (mounted.current) observer();
+    mounted.current = true;
+  }, deps);
+}
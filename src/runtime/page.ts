@@ -0,0 +1,110 @@
+// 页面生命周期适配，由 mini2react 生成，请勿手动修改
+import { useEffect, useRef } from "react";
+
+type Callback<T = any> = ((arg: T) => void) | undefined;
+
+// 保存最新的回调，避免每次渲染重新注册事件
+function useLatest<T>(value: T) {
+  const ref = useRef(value);
+  ref.current = value;
+  return ref;
+}
+
+/** 当前页面的查询参数，hash 路由下取 `#/path?query` 中的 query */
+export function getPageQuery(): Record<string, string> {
+  const { hash, search } = window.location;
+  const query = hash.includes("?") ? hash.slice(hash.indexOf("?")) : search;
+  return Object.fromEntries(new URLSearchParams(query));
+}
+
+/** 对应 onLoad(query)，页面挂载时以路由参数调用一次 */
+export function useLoad(onLoad: Callback<Record<string, string>>) {
+  const callback = useLatest(onLoad);
+  useEffect(() => {
+    callback.current?.(getPageQuery());
+  }, []);
+}
+
+/** 对应 onShow，页面挂载和重新可见时调用 */
+export function useShow(onShow: Callback<void>) {
+  const callback = useLatest(onShow);
+  useEffect(() => {
+    callback.current?.();
+    const listener = () => document.visibilityState === "visible" && callback.current?.();
+    document.addEventListener("visibilitychange", listener);
+    return () => document.removeEventListener("visibilitychange", listener);
+  }, []);
+}
+
+/** 对应 onReady，首次渲染完成后调用一次 */
+export function useReady(onReady: Callback<void>) {
+  const callback = useLatest(onReady);
+  useEffect(() => {
+    callback.current?.();
+  }, []);
+}
+
+/** 对应 onHide，页面不可见和卸载时调用 */
+export function useHide(onHide: Callback<void>) {
+  const callback = useLatest(onHide);
+  useEffect(() => {
+    const listener = () => document.visibilityState === "hidden" && callback.current?.();
+    document.addEventListener("visibilitychange", listener);
+    return () => {
+      document.removeEventListener("visibilitychange", listener);
+      callback.current?.();
+    };
+  }, []);
+}
+
+/** 对应 onUnload，页面卸载时调用 */
+export function useUnload(onUnload: Callback<void>) {
+  const callback = useLatest(onUnload);
+  useEffect(() => () => callback.current?.(), []);
+}
+
+/** 对应 onPageScroll({ scrollTop }) */
+export function usePageScroll(onPageScroll: Callback<{ scrollTop: number }>) {
+  const callback = useLatest(onPageScroll);
+  useEffect(() => {
+    const listener = () => callback.current?.({ scrollTop: window.scrollY });
+    window.addEventListener("scroll", listener, { passive: true });
+    return () => window.removeEventListener("scroll", listener);
+  }, []);
+}
+
+/** 对应 onReachBottom，滚动到距底部 distance 以内时调用，离开后才会再次触发 */
+export function useReachBottom(onReachBottom: Callback<void>, distance = 50) {
+  const callback = useLatest(onReachBottom);
+  useEffect(() => {
+    let reached = false;
+    const listener = () => {
+      const bottom = document.documentElement.scrollHeight - window.innerHeight - window.scrollY;
+      if (bottom <= distance && !reached) callback.current?.();
+      reached = bottom <= distance;
+    };
+    window.addEventListener("scroll", listener, { passive: true });
+    return () => window.removeEventListener("scroll", listener);
+  }, [distance]);
+}
+
+/** 对应 onPullDownRefresh，页面在顶部时下拉超过 threshold 触发 */
+export function usePullDownRefresh(onPullDownRefresh: Callback<void>, threshold = 80) {
+  const callback = useLatest(onPullDownRefresh);
+  useEffect(() => {
+    let startY: number | null = null;
+    const start = (e: TouchEvent) => {
+      startY = window.scrollY <= 0 ? e.touches[0].clientY : null;
+    };
+    const end = (e: TouchEvent) => {
+      if (startY !== null && e.changedTouches[0].clientY - startY > threshold) callback.current?.();
+      startY = null;
+    };
+    window.addEventListener("touchstart", start, { passive: true });
+    window.addEventListener("touchend", end);
+    return () => {
+      window.removeEventListener("touchstart", start);
+      window.removeEventListener("touchend", end);
+    };
+  }, [threshold]);
+}
@@ -0,0 +1,143 @@
+// 节点查询与组件实例，对应 my.createSelectorQuery 和 this.$selectComponent，由 mini2react 生成，请勿手动修改
+import { useEffect, useImperativeHandle, useMemo, useRef } from "react";
+
+type Instance = Record<string, any>;
+
+// 按 id 注册的组件实例
+const instances = new Map<string, Instance>();
+
+/**
+ * 注册组件实例：父组件的 ref 方法（以 componentRef 传入）和 selectComponent("#id") 得到的都是该实例，
+ * 实例是一个代理，访问属性时读取最新一次渲染的方法和数据
+ */
+export function useComponentInstance(props: Record<string, any>, instance: Instance) {
+  const latest = useRef(instance);
+  latest.current = instance;
+  const handle = useMemo(() => new Proxy({} as Instance, { get: (_, key) => latest.current[key as string] }), []);
+  useImperativeHandle(props.componentRef, () => handle, [handle]);
+  const id = props.id;
+  useEffect(() => {
+    if (!id) return;
+    instances.set(id, handle);
+    return () => {
+      if (instances.get(id) === handle) instances.delete(id);
+    };
+  }, [id, handle]);
+}
+
+/** 对应 this.$selectComponent，只支持 `#id` 选择器 */
+export function selectComponent(selector: string): Instance | undefined {
+  if (!selector.startsWith("#")) {
+    console.warn(`selectComponent only supports id selectors: ${selector}`);
+    return undefined;
+  }
+  return instances.get(selector.slice(1));
+}
+
+export interface BoundingClientRect {
+  id: string;
+  dataset: Record<string, string>;
+  left: number;
+  right: number;
+  top: number;
+  bottom: number;
+  width: number;
+  height: number;
+}
+
+export interface ScrollOffset {
+  id: string;
+  dataset: Record<string, string>;
+  scrollTop: number;
+  scrollLeft: number;
+}
+
+type Callback = ((result: any) => void) | undefined;
+
+function rect(element: Element): BoundingClientRect {
+  const { left, right, top, bottom, width, height } = element.getBoundingClientRect();
+  const dataset = element instanceof HTMLElement ? { ...element.dataset } : {};
+  return { id: element.id, dataset, left, right, top, bottom, width, height };
+}
+
+function viewport(): BoundingClientRect {
+  const { innerWidth: width, innerHeight: height } = window;
+  return { id: "", dataset: {}, left: 0, right: width, top: 0, bottom: height, width, height };
+}
+
+function offset(element: Element | null): ScrollOffset {
+  if (!element) {
+    const scroller = document.scrollingElement ?? document.documentElement;
+    return { id: "", dataset: {}, scrollTop: scroller.scrollTop, scrollLeft: scroller.scrollLeft };
+  }
+  const dataset = element instanceof HTMLElement ? { ...element.dataset } : {};
+  return { id: element.id, dataset, scrollTop: element.scrollTop, scrollLeft: element.scrollLeft };
+}
+
+class NodesRef {
+  constructor(
+    private query: SelectorQuery,
+    private selector: string | null,
+    private all: boolean,
+  ) {}
+
+  private elements(): Element[] {
+    if (this.selector === null) return [];
+    if (this.all) return Array.from(document.querySelectorAll(this.selector));
+    const element = document.querySelector(this.selector);
+    return element ? [element] : [];
+  }
+
+  private push(read: (element: Element | null) => any, callback: Callback) {
+    return this.query.push(() => {
+      if (this.selector === null) return read(null);
+      const results = this.elements().map(read);
+      return this.all ? results : results[0] ?? null;
+    }, callback);
+  }
+
+  boundingClientRect(callback?: Callback) {
+    return this.push((element) => (element ? rect(element) : viewport()), callback);
+  }
+
+  scrollOffset(callback?: Callback) {
+    return this.push(offset, callback);
+  }
+}
+
+/** 对应 my.createSelectorQuery()，exec 在下一帧读取 DOM，此时本次渲染已经提交 */
+export class SelectorQuery {
+  private tasks: { read: () => any; callback: Callback }[] = [];
+
+  select(selector: string) {
+    return new NodesRef(this, selector, false);
+  }
+
+  selectAll(selector: string) {
+    return new NodesRef(this, selector, true);
+  }
+
+  selectViewport() {
+    return new NodesRef(this, null, false);
+  }
+
+  push(read: () => any, callback: Callback) {
+    this.tasks.push({ read, callback });
+    return this;
+  }
+
+  exec(callback?: (results: any[]) => void) {
+    requestAnimationFrame(() => {
+      const results = this.tasks.map(({ read, callback }) => {
+        const result = read();
+        callback?.(result);
+        return result;
+      });
+      callback?.(results);
+    });
+  }
+}
+
+export function createSelectorQuery() {
+  return new SelectorQuery();
+}
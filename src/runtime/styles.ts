@@ -0,0 +1,60 @@
+// 类名拼接、CSS Modules 类名映射和行内样式解析，由 mini2react 生成，请勿手动修改
+
+/**
+ * 按组件样式映射运行时拼接出的类名
+ *
+ * 组件样式中声明的类名替换为 CSS Modules 生成的类名，externalClasses 替换为父组件传入的类名，其余保持全局类名
+ */
+export function scopedClass(
+  styles: Record<string, string>,
+  names: string,
+  external: Record<string, string | undefined> = {},
+) {
+  return String(names ?? "")
+    .split(/\s+/)
+    .filter(Boolean)
+    .map((name) => (name in external ? external[name] ?? "" : styles[name] ?? name))
+    .filter(Boolean)
+    .join(" ");
+}
+
+// css 属性名转换为 React 样式对象的键名，自定义属性保持原样
+function styleKey(property: string) {
+  if (property.startsWith("--")) return property;
+  return property
+    .toLowerCase()
+    .replace(/^-ms-/, "ms-")
+    .replace(/-([a-z])/g, (_, c: string) => c.toUpperCase());
+}
+
+/** 运行时拼接的行内样式字符串转换为 React 样式对象，rpx 按生成时的换算单位转换 */
+export function styleObject(style: string | Record<string, any> | undefined, rpx = "0.133333vw") {
+  if (style && typeof style === "object") return style;
+  const result: Record<string, string> = {};
+  for (const declaration of String(style ?? "").split(/;(?![^(]*\))/)) {
+    const index = declaration.indexOf(":");
+    if (index < 0) continue;
+    const property = declaration.slice(0, index).trim();
+    const value = declaration
+      .slice(index + 1)
+      .trim()
+      .replace(/(-?\d*\.?\d+)rpx\b/g, (_, n: string) => `calc(${n} * ${rpx})`);
+    if (property && value) result[styleKey(property)] = value;
+  }
+  return result;
+}
+
+type ClassValue = string | number | boolean | null | undefined | ClassValue[] | Record<string, any>;
+
+/** 拼接类名，忽略空值并合并多余的空白，数组递归展开，对象取值为真的键名 */
+export function cx(...args: ClassValue[]) {
+  const names: string[] = [];
+  const add = (value: ClassValue) => {
+    if (!value || value === true) return;
+    if (Array.isArray(value)) value.forEach(add);
+    else if (typeof value === "object") names.push(...Object.keys(value).filter((key) => value[key]));
+    else names.push(...String(value).split(/\s+/).filter(Boolean));
+  };
+  args.forEach(add);
+  return names.join(" ");
+}
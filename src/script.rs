@@ -0,0 +1,1375 @@
+//! 小程序 js/ts 脚本解析，基于 oxc 从 `Component()` / `Page()` / `App()` 的选项对象中提取各部分源码
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs,
+    path::Path,
+};
+use oxc_allocator::Allocator;
+use oxc_ast::ast::{
+    Argument, ArrayExpressionElement, ArrowFunctionExpression, BindingPattern, CallExpression, Expression,
+    ExpressionStatement, Function, IdentifierReference, ImportDeclarationSpecifier, ObjectExpression,
+    ObjectPropertyKind, Program, Statement, StaticMemberExpression, ThisExpression, VariableDeclaration,
+};
+use oxc_ast_visit::{walk, Visit};
+use oxc_parser::Parser;
+use oxc_span::{GetSpan, SourceType, Span};
+use crate::expression::quote_string;
+
+/// 脚本注册的实例类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptKind {
+    Component,
+    Page,
+    App,
+}
+
+impl ScriptKind {
+    fn from_callee(name: &str) -> Option<Self> {
+        match name {
+            "Component" => Some(ScriptKind::Component),
+            "Page" => Some(ScriptKind::Page),
+            "App" => Some(ScriptKind::App),
+            _ => None,
+        }
+    }
+
+    /// 该类型实例的生命周期函数名
+    pub fn lifecycles(&self) -> &'static [&'static str] {
+        match self {
+            ScriptKind::Component => &["onInit", "deriveDataFromProps", "didMount", "didUpdate", "didUnmount", "onError"],
+            ScriptKind::Page => &[
+                "onLoad",
+                "onShow",
+                "onReady",
+                "onHide",
+                "onUnload",
+                "onTitleClick",
+                "onOptionMenuClick",
+                "onPullDownRefresh",
+                "onReachBottom",
+                "onPageScroll",
+                "onShareAppMessage",
+                "onTabItemTap",
+            ],
+            ScriptKind::App => &["onLaunch", "onShow", "onHide", "onError", "onShareAppMessage"],
+        }
+    }
+}
+
+/// 选项对象中的函数成员，参数、类型注解和函数体均保留源码
+#[derive(Debug, Clone)]
+pub struct Method {
+    pub name: String,
+    pub is_async: bool,
+    /// 泛型参数声明
+    pub type_params: String,
+    /// 含括号和类型注解的参数列表
+    pub params: String,
+    /// 返回值类型注解，含前导冒号
+    pub return_type: String,
+    /// 含花括号的函数体
+    pub body: String,
+    /// 成员前的注释
+    pub comments: String,
+}
+
+impl Method {
+    /// 输出为顶层具名函数声明
+    pub fn to_function(&self) -> String {
+        format!(
+            "{}{}function {}{}{}{} {}",
+            self.comments,
+            if self.is_async { "async " } else { "" },
+            self.name,
+            self.type_params,
+            self.params,
+            self.return_type,
+            self.body
+        )
+    }
+}
+
+/// props 中声明的属性，默认值保留源码
+#[derive(Debug, Clone)]
+pub struct Prop {
+    pub name: String,
+    pub default: String,
+    /// 由默认值推断出的 TypeScript 类型
+    pub ts_type: &'static str,
+    pub comments: String,
+}
+
+/// observers 监听的字段
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObservedField {
+    /// `**`，监听全部数据
+    All,
+    /// 根字段名和读取该字段路径的表达式，`a.**` 与 `a` 相同
+    Path(String, String),
+}
+
+/// observers 中的一项，字段的值依次作为参数传给监听函数
+#[derive(Debug, Clone)]
+pub struct Observer {
+    pub fields: Vec<ObservedField>,
+    pub method: Method,
+}
+
+/// 选项对象中的普通成员，值保留源码
+#[derive(Debug, Clone)]
+pub struct Property {
+    pub name: String,
+    pub value: String,
+    pub comments: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Script {
+    pub kind: Option<ScriptKind>,
+    /// 顶层 import 语句
+    pub imports: Vec<String>,
+    /// 除 import 和实例注册外的其它顶层语句
+    pub declarations: Vec<String>,
+    /// `data` 对象字面量中的字段
+    pub data: Vec<Property>,
+    /// `data` 为函数时的函数体
+    pub data_factory: Option<String>,
+    pub props: Vec<Prop>,
+    pub methods: Vec<Method>,
+    pub lifecycles: Vec<Method>,
+    /// 其它未单独处理的选项，如 options、externalClasses
+    pub options: Vec<Property>,
+    /// App 选项中值不是对象或数组字面量的字段，保存在 ref 上，`this.x` 转换为 `x.current`
+    pub ref_fields: BTreeSet<String>,
+    /// mixins 和 behaviors 中引用的导入名和模块路径，按声明顺序排列
+    pub mixins: Vec<(String, String)>,
+    /// 合并进来的 mixin 生命周期，函数已按 mixin 名重命名，先于组件自身的同名生命周期执行
+    pub mixin_lifecycles: Vec<(String, Method)>,
+    pub observers: Vec<Observer>,
+    /// 函数体中 setData 和 `this.data.x` 访问到的状态名
+    pub state: BTreeSet<String>,
+    /// 函数体中整体访问了 `this.data`
+    pub uses_data: bool,
+    /// 函数体中整体访问了 `this.props`
+    pub uses_props: bool,
+    /// 函数体中调用了 getApp()
+    pub uses_app: bool,
+    /// 顶层语句中调用了 getApp()
+    pub declarations_use_app: bool,
+    /// 函数体中访问了无法对应到状态、props 或方法的实例属性
+    pub uses_instance: bool,
+    /// 存在无法静态展开的 setData 调用
+    pub uses_setters: bool,
+    pub runtime_imports: BTreeSet<&'static str>,
+    pub warnings: Vec<String>,
+}
+
+impl Script {
+    pub fn method(&self, name: &str) -> Option<&Method> {
+        self.methods.iter().find(|method| method.name == name)
+    }
+
+    /// 生命周期触发时依次调用的函数名，mixin 在前
+    pub fn lifecycle_calls(&self, lifecycle: &str) -> Vec<&str> {
+        self.mixin_lifecycles
+            .iter()
+            .filter(|(name, _)| name == lifecycle)
+            .map(|(_, method)| method.name.as_str())
+            .chain(self.lifecycles.iter().filter(|method| method.name == lifecycle).map(|method| method.name.as_str()))
+            .collect()
+    }
+
+    /// 供 mixin 和组件互相通过 this 访问的方法和 props 名
+    pub fn inherited(&self) -> Inherited {
+        Inherited {
+            members: self.methods.iter().chain(&self.lifecycles).map(|method| method.name.clone()).collect(),
+            props: self.props.iter().map(|prop| prop.name.clone()).collect(),
+            shadowed: vec![],
+        }
+    }
+
+    /// data 中声明和 setData 中出现的全部状态名
+    pub fn state_names(&self) -> BTreeSet<String> {
+        self.data.iter().map(|field| field.name.clone()).chain(self.state.iter().cloned()).collect()
+    }
+
+    /// 合并一个 mixin，已有的同名成员优先，生命周期都会保留
+    ///
+    /// mixins 按声明的逆序合并，因此组件自身优先于 mixin，后声明的 mixin 优先于先声明的，
+    /// 生命周期按声明顺序在组件自身之前执行
+    pub fn merge_mixin(&mut self, name: &str, mixin: Script) {
+        for field in mixin.data {
+            if !self.data.iter().any(|own| own.name == field.name) {
+                self.data.push(field);
+            }
+        }
+        if mixin.data_factory.is_some() {
+            self.warnings.push(format!("data function in mixin is not supported: `{}`", name));
+        }
+        for prop in mixin.props {
+            if !self.props.iter().any(|own| own.name == prop.name) {
+                self.props.push(prop);
+            }
+        }
+        for method in mixin.methods {
+            if self.method(&method.name).is_none() {
+                self.methods.push(method);
+            }
+        }
+        // 后合并的 mixin 先执行，插入到前面
+        let lifecycles = mixin.mixin_lifecycles.into_iter().chain(mixin.lifecycles.into_iter().map(|mut method| {
+            let lifecycle = method.name.clone();
+            method.name = prefixed(name, &lifecycle);
+            (lifecycle, method)
+        }));
+        let lifecycles = lifecycles.collect::<Vec<_>>();
+        self.mixin_lifecycles.splice(0..0, lifecycles);
+        let observers = mixin.observers.into_iter().map(|mut observer| {
+            observer.method.name = prefixed(name, &observer.method.name);
+            observer
+        });
+        let observers = observers.collect::<Vec<_>>();
+        self.observers.splice(0..0, observers);
+        for option in mixin.options {
+            if !self.options.iter().any(|own| own.name == option.name) {
+                self.options.push(option);
+            }
+        }
+        let imports = mixin.imports.into_iter().filter(|import| !self.imports.contains(import)).collect::<Vec<_>>();
+        self.imports.splice(0..0, imports);
+        self.declarations.splice(0..0, mixin.declarations);
+        self.state.extend(mixin.state);
+        self.uses_data |= mixin.uses_data;
+        self.uses_props |= mixin.uses_props;
+        self.uses_app |= mixin.uses_app;
+        self.declarations_use_app |= mixin.declarations_use_app;
+        self.uses_instance |= mixin.uses_instance;
+        self.uses_setters |= mixin.uses_setters;
+        self.runtime_imports.extend(mixin.runtime_imports);
+    }
+}
+
+/// mixin 的生命周期和监听函数加上 mixin 名作为前缀，如 `loggerDidMount`
+fn prefixed(prefix: &str, name: &str) -> String {
+    let mut chars = name.chars();
+    let first = chars.next().map(|c| c.to_ascii_uppercase()).into_iter();
+    format!("{}{}", prefix, first.chain(chars).collect::<String>())
+}
+
+/// 组件与其 mixins 之间可以通过 this 互相访问的成员
+#[derive(Debug, Default, Clone)]
+pub struct Inherited {
+    pub members: Vec<String>,
+    pub props: Vec<String>,
+    /// 与状态同名的 props，不从 props 对象中解构，`this.props.x` 保留为 `props.x`
+    pub shadowed: Vec<String>,
+}
+
+/// `this.setData` 中的一项更新，`path` 为根字段下的路径
+#[derive(Debug)]
+struct Update {
+    root: String,
+    path: Vec<String>,
+    value: Span,
+}
+
+#[derive(Debug)]
+enum Edit {
+    Text(String),
+    /// `patch` 为无法静态展开的参数，`statement` 为整条语句被替换时所在行的缩进
+    SetData { updates: Vec<Update>, patch: Option<Span>, callback: Option<Span>, statement: Option<usize> },
+}
+
+struct Extractor<'s, 'a> {
+    source: &'s str,
+    program: &'s Program<'a>,
+    script: Script,
+    /// 可以通过 this 直接调用的方法和生命周期函数
+    members: HashSet<String>,
+    /// props 中声明的属性名，按声明顺序排列
+    prop_names: Vec<String>,
+    /// App 选项中的非函数成员，如 globalData
+    fields: HashSet<String>,
+    /// 组件或 mixin 从对方合并来的成员
+    inherited: Inherited,
+    /// 是否按 mixin 模块解析，选项对象是默认导出
+    mixin: bool,
+    /// 默认导入的名称到模块路径和 import 语句位置的映射，用于查找 mixins
+    import_sources: HashMap<String, (String, Span)>,
+    /// 已合并为 mixin 的 import 语句，不再输出
+    mixin_imports: HashSet<Span>,
+    /// 函数体中 this 相关的源码替换，按起始位置排列
+    edits: Vec<(Span, Edit)>,
+    /// 直接位于语句列表中的表达式语句的起始位置，其中的 setData 可以展开为多条语句
+    list_statements: HashSet<u32>,
+    /// 箭头函数表达式体的起始位置，不是真正的语句
+    expression_bodies: HashSet<u32>,
+    /// 当前函数中指向 this 的变量，如 `const self = this`，按 this 转换
+    this_aliases: HashSet<String>,
+}
+
+impl<'s, 'a> Extractor<'s, 'a> {
+    fn slice(&self, span: Span) -> String {
+        dedent(&self.render(span), self.indent_at(span.start))
+    }
+
+    /// 输出应用了替换后的源码片段
+    fn render(&self, span: Span) -> String {
+        let mut out = String::new();
+        let mut pos = span.start;
+        for (edit_span, edit) in &self.edits {
+            if edit_span.start < pos || edit_span.end > span.end {
+                continue;
+            }
+            out.push_str(&self.source[pos as usize..edit_span.start as usize]);
+            out.push_str(&self.render_edit(edit));
+            pos = edit_span.end;
+        }
+        out.push_str(&self.source[pos as usize..span.end as usize]);
+        out
+    }
+
+    fn render_edit(&self, edit: &Edit) -> String {
+        let (updates, patch, callback, statement) = match edit {
+            Edit::Text(text) => return text.clone(),
+            Edit::SetData { updates, patch, callback, statement } => (updates, patch, callback, statement),
+        };
+        let mut calls = match patch {
+            Some(patch) => vec![format!("applyData(stateSetters, {})", self.render(*patch))],
+            None => updates
+                .iter()
+                .map(|update| {
+                    let value = self.render(update.value);
+                    if update.path.is_empty() {
+                        format!("{}({})", state_setter(&update.root), value)
+                    } else {
+                        format!(
+                            "{}((prev: any) => setIn(prev, [{}], {}))",
+                            state_setter(&update.root),
+                            update.path.join(", "),
+                            value
+                        )
+                    }
+                })
+                .collect::<Vec<_>>(),
+        };
+        // React 没有 setData 回调，回调推迟到状态更新之后执行
+        if let Some(callback) = callback {
+            calls.push(format!("setTimeout({})", self.render(*callback)));
+        }
+        match statement {
+            Some(indent) => {
+                let calls = calls.iter().map(|call| format!("{};", call)).collect::<Vec<_>>();
+                calls.join(&format!("\n{}", " ".repeat(*indent)))
+            }
+            None if calls.is_empty() => "undefined".to_string(),
+            None if calls.len() == 1 => calls.remove(0),
+            None => format!("({})", calls.join(", ")),
+        }
+    }
+
+    /// 节点所在行的缩进宽度
+    /// this 或指向 this 的变量
+    fn is_this(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::ThisExpression(_) => true,
+            Expression::Identifier(ident) => self.this_aliases.contains(ident.name.as_str()),
+            _ => false,
+        }
+    }
+
+    fn is_this_member(&self, member: &StaticMemberExpression, name: &str) -> bool {
+        self.is_this(&member.object) && member.property.name == name
+    }
+
+    fn line(&self, span: Span) -> usize {
+        self.source[..span.start as usize].lines().count().max(1)
+    }
+
+    /// 独占一行的节点连同缩进和换行一起删除
+    fn line_span(&self, span: Span) -> Span {
+        let line_start = self.source[..span.start as usize].rfind('\n').map_or(0, |i| i + 1);
+        let rest = &self.source[span.end as usize..];
+        let own_line = self.source[line_start..span.start as usize].trim().is_empty()
+            && rest.trim_start_matches([' ', '\t']).starts_with('\n');
+        match own_line {
+            true => Span::new(line_start as u32, (span.end as usize + rest.find('\n').unwrap() + 1) as u32),
+            false => span,
+        }
+    }
+
+    fn indent_at(&self, pos: u32) -> usize {
+        let line_start = self.source[..pos as usize].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        self.source[line_start..].chars().take_while(|c| *c == ' ' || *c == '\t').count()
+    }
+
+    /// 紧挨在节点前的注释，每条注释单独一行
+    fn comments(&self, span: Span) -> String {
+        self.program
+            .comments
+            .iter()
+            .filter(|comment| comment.attached_to == span.start && comment.is_leading())
+            .map(|comment| format!("{}\n", self.slice(comment.span)))
+            .collect()
+    }
+
+    fn warn(&mut self, value: &str, reason: &str) {
+        self.script.warnings.push(format!("{}: `{}`", reason, value));
+    }
+
+    fn visit_program(&mut self) {
+        for statement in &self.program.body {
+            let Statement::ImportDeclaration(import) = statement else {
+                continue;
+            };
+            let specifiers = import.specifiers.as_ref().map(|specifiers| specifiers.as_slice());
+            if let Some([ImportDeclarationSpecifier::ImportDefaultSpecifier(specifier)]) = specifiers {
+                let source = import.source.value.to_string();
+                self.import_sources.insert(specifier.local.name.to_string(), (source, import.span));
+            }
+        }
+        let mut imports = vec![];
+        for statement in &self.program.body {
+            if let Statement::ImportDeclaration(import) = statement {
+                let text = format!("{}{}", self.comments(import.span), self.slice(import.span));
+                imports.push((import.span, text));
+                continue;
+            }
+            let registration = match self.mixin {
+                true => mixin_options(self.source, statement).map(|options| (ScriptKind::Component, Some(options))),
+                false => registration(statement),
+            };
+            if let Some((kind, options)) = registration {
+                if self.script.kind.is_some() {
+                    self.warn(&format!("{:?}", kind), "duplicate registration");
+                    continue;
+                }
+                self.script.kind = Some(kind);
+                match options {
+                    Some(options) => self.visit_options(kind, options),
+                    None => self.warn(&format!("{:?}", kind), "options must be an object literal"),
+                }
+                continue;
+            }
+            let span = statement.span();
+            let text = format!("{}{}", self.comments(span), self.slice(span));
+            self.script.declarations.push(text);
+            let mut finder = GetAppFinder(false);
+            finder.visit_statement(statement);
+            self.script.declarations_use_app |= finder.0;
+        }
+        self.script.imports = imports
+            .into_iter()
+            .filter(|(span, _)| !self.mixin_imports.contains(span))
+            .map(|(_, text)| text)
+            .collect();
+    }
+
+    fn visit_options(&mut self, kind: ScriptKind, options: &ObjectExpression<'a>) {
+        self.members = member_names(kind, options);
+        self.members.extend(self.inherited.members.iter().cloned());
+        self.prop_names = prop_names(options);
+        for prop in &self.inherited.props {
+            if !self.prop_names.contains(prop) {
+                self.prop_names.push(prop.clone());
+            }
+        }
+        self.prop_names.retain(|prop| !self.inherited.shadowed.contains(prop));
+        if kind == ScriptKind::App {
+            self.fields = field_names(options);
+            self.script.ref_fields = ref_field_names(options);
+        }
+        for property in &options.properties {
+            let ObjectPropertyKind::ObjectProperty(property) = property else {
+                self.warn(&self.slice(property.span()), "spread options are not supported");
+                continue;
+            };
+            let Some(name) = property.key.static_name().map(|name| name.to_string()) else {
+                self.warn(&self.slice(property.span), "computed option names are not supported");
+                continue;
+            };
+            let comments = self.comments(property.span);
+            match (name.as_str(), &property.value) {
+                ("data", Expression::ObjectExpression(object)) => self.script.data = self.properties(object),
+                // data 为函数时在初始化时调用一次
+                ("data", value) => {
+                    // 返回的字段声明为状态，初始值从 data 函数的返回值中读取
+                    match data_factory_fields(value) {
+                        Some(fields) => self.script.state.extend(fields),
+                        None => self.warn(
+                            &name,
+                            "fields returned by the data function cannot be determined statically, declare them in data",
+                        ),
+                    }
+                    let data = match self.method(&name, value, String::new()) {
+                        Some(method) => method.body,
+                        None => format!("{{\n  return {};\n}}", self.slice(value.span())),
+                    };
+                    self.script.data_factory = Some(data);
+                }
+                ("props", Expression::ObjectExpression(object)) if kind == ScriptKind::Component => {
+                    self.script.props = self.props(object);
+                }
+                ("observers", Expression::ObjectExpression(object)) if kind == ScriptKind::Component => {
+                    self.observers(object);
+                }
+                ("mixins" | "behaviors", Expression::ArrayExpression(array)) if kind == ScriptKind::Component => {
+                    for element in &array.elements {
+                        let source = match element {
+                            ArrayExpressionElement::Identifier(ident) => {
+                                self.import_sources.get(ident.name.as_str()).map(|source| (ident.name.to_string(), source))
+                            }
+                            _ => None,
+                        };
+                        match source {
+                            Some((name, (source, span))) => {
+                                self.script.mixins.push((name, source.clone()));
+                                self.mixin_imports.insert(*span);
+                            }
+                            None => self.warn(&self.slice(element.span()), "mixin must be a default import"),
+                        }
+                    }
+                }
+                ("methods", Expression::ObjectExpression(object)) if kind == ScriptKind::Component => {
+                    for property in &object.properties {
+                        let ObjectPropertyKind::ObjectProperty(property) = property else {
+                            self.warn(&self.slice(property.span()), "spread methods are not supported");
+                            continue;
+                        };
+                        let Some(name) = property.key.static_name().map(|name| name.to_string()) else {
+                            self.warn(&self.slice(property.span), "computed method names are not supported");
+                            continue;
+                        };
+                        let comments = self.comments(property.span);
+                        match self.method(&name, &property.value, comments) {
+                            Some(method) => self.script.methods.push(method),
+                            None => self.warn(&name, "method is not a function"),
+                        }
+                    }
+                }
+                _ => match self.method(&name, &property.value, comments.clone()) {
+                    Some(method) if kind.lifecycles().contains(&name.as_str()) => self.script.lifecycles.push(method),
+                    // Page 和 App 的事件处理函数直接写在选项对象上
+                    Some(method) => self.script.methods.push(method),
+                    None => {
+                        let value = self.slice(property.value.span());
+                        self.script.options.push(Property { name, value, comments });
+                    }
+                },
+            }
+        }
+    }
+
+    fn properties(&mut self, object: &ObjectExpression<'a>) -> Vec<Property> {
+        let mut properties = vec![];
+        for property in &object.properties {
+            let name = match property {
+                ObjectPropertyKind::ObjectProperty(property) if !property.computed => property.key.static_name(),
+                _ => None,
+            };
+            match (property, name) {
+                (ObjectPropertyKind::ObjectProperty(property), Some(name)) => properties.push(Property {
+                    name: name.to_string(),
+                    value: self.slice(property.value.span()),
+                    comments: self.comments(property.span),
+                }),
+                _ => self.warn(&self.slice(property.span()), "only static fields are supported"),
+            }
+        }
+        properties
+    }
+
+    /// observers 的每一项转换为一个监听函数，键中的字段无法表达时跳过该项
+    fn observers(&mut self, object: &ObjectExpression<'a>) {
+        for property in &object.properties {
+            let ObjectPropertyKind::ObjectProperty(property) = property else {
+                self.warn(&self.slice(property.span()), "spread observers are not supported");
+                continue;
+            };
+            let Some(key) = property.key.static_name().map(|name| name.to_string()) else {
+                self.warn(&self.slice(property.span), "computed observer keys are not supported");
+                continue;
+            };
+            let Some(fields) = key.split(',').map(|field| observed_field(field.trim())).collect::<Option<Vec<_>>>() else {
+                self.warn(&key, "observer path is not supported");
+                continue;
+            };
+            for field in &fields {
+                match field {
+                    ObservedField::All => self.script.uses_data = true,
+                    ObservedField::Path(root, _) if !self.prop_names.contains(root) => {
+                        self.script.state.insert(root.clone());
+                    }
+                    ObservedField::Path(..) => {}
+                }
+            }
+            let mut name = observer_name(&key);
+            let base = name.clone();
+            let mut index = 1;
+            while self.members.contains(&name) || self.script.observers.iter().any(|o| o.method.name == name) {
+                index += 1;
+                name = format!("{}{}", base, index);
+            }
+            let comments = self.comments(property.span);
+            match self.method(&name, &property.value, comments) {
+                Some(method) => self.script.observers.push(Observer { fields, method }),
+                None => self.warn(&key, "observer is not a function"),
+            }
+        }
+    }
+
+    fn props(&mut self, object: &ObjectExpression<'a>) -> Vec<Prop> {
+        let mut props = vec![];
+        for property in &object.properties {
+            let name = match property {
+                ObjectPropertyKind::ObjectProperty(property) if !property.computed => property.key.static_name(),
+                _ => None,
+            };
+            let (ObjectPropertyKind::ObjectProperty(property), Some(name)) = (property, name) else {
+                self.warn(&self.slice(property.span()), "only static props are supported");
+                continue;
+            };
+            let name = name.to_string();
+            // `onChange() {}` 简写的默认值输出为函数表达式
+            let default = match self.method(&name, &property.value, String::new()) {
+                Some(method) if property.method => format!(
+                    "{}function{}{}{} {}",
+                    if method.is_async { "async " } else { "" },
+                    method.type_params,
+                    method.params,
+                    method.return_type,
+                    method.body
+                ),
+                _ => self.slice(property.value.span()),
+            };
+            props.push(Prop {
+                name,
+                default,
+                ts_type: prop_type(&property.value),
+                comments: self.comments(property.span),
+            });
+        }
+        props
+    }
+
+    fn method(&mut self, name: &str, value: &Expression<'a>, comments: String) -> Option<Method> {
+        match value {
+            Expression::FunctionExpression(function) => self.function(name, function, comments),
+            Expression::ArrowFunctionExpression(arrow) => Some(self.arrow(name, arrow, comments)),
+            _ => None,
+        }
+    }
+
+    fn function(&mut self, name: &str, function: &Function<'a>, comments: String) -> Option<Method> {
+        self.this_aliases.clear();
+        self.visit_function_body(function.body.as_ref()?);
+        Some(Method {
+            name: name.to_string(),
+            is_async: function.r#async,
+            type_params: function.type_parameters.as_ref().map(|t| self.slice(t.span)).unwrap_or_default(),
+            params: self.params(function.params.span),
+            return_type: function.return_type.as_ref().map(|t| self.slice(t.span)).unwrap_or_default(),
+            body: self.slice(function.body.as_ref()?.span),
+            comments,
+        })
+    }
+
+    fn arrow(&mut self, name: &str, arrow: &ArrowFunctionExpression<'a>, comments: String) -> Method {
+        self.mark_expression_body(arrow);
+        self.this_aliases.clear();
+        self.visit_function_body(&arrow.body);
+        // 表达式体的箭头函数补全为 return 语句
+        let body = match (arrow.expression, arrow.body.statements.first()) {
+            (true, Some(Statement::ExpressionStatement(statement))) => {
+                format!("{{\n  return {};\n}}", self.slice(statement.expression.span()))
+            }
+            _ => self.slice(arrow.body.span),
+        };
+        Method {
+            name: name.to_string(),
+            is_async: arrow.r#async,
+            type_params: arrow.type_parameters.as_ref().map(|t| self.slice(t.span)).unwrap_or_default(),
+            params: self.params(arrow.params.span),
+            return_type: arrow.return_type.as_ref().map(|t| self.slice(t.span)).unwrap_or_default(),
+            body,
+            comments,
+        }
+    }
+
+    fn mark_expression_body(&mut self, arrow: &ArrowFunctionExpression<'a>) {
+        if let (true, Some(statement)) = (arrow.expression, arrow.body.statements.first()) {
+            self.expression_bodies.insert(statement.span().start);
+        }
+    }
+
+    /// 单参数箭头函数的参数没有括号
+    fn params(&self, span: Span) -> String {
+        let params = self.slice(span);
+        if params.starts_with('(') {
+            params
+        } else {
+            format!("({})", params)
+        }
+    }
+
+    /// 展开 `this.setData({...})`，不是 setData 调用时返回 false
+    fn set_data(&mut self, call: &CallExpression<'a>, statement: Option<Span>) -> bool {
+        let Expression::StaticMemberExpression(callee) = call.callee.without_parentheses() else {
+            return false;
+        };
+        if !self.is_this_member(callee, "setData") {
+            return false;
+        }
+        let mut updates = vec![];
+        let mut patch = None;
+        match call.arguments.first() {
+            Some(Argument::ObjectExpression(object)) => {
+                for property in &object.properties {
+                    let update = match property {
+                        ObjectPropertyKind::ObjectProperty(property) if !property.computed => property
+                            .key
+                            .static_name()
+                            .and_then(|key| parse_data_path(&key))
+                            .map(|(root, path)| Update { root, path, value: property.value.span() }),
+                        _ => None,
+                    };
+                    match update {
+                        Some(update) => updates.push(update),
+                        None => {
+                            patch = Some(object.span);
+                            break;
+                        }
+                    }
+                }
+            }
+            Some(argument) => patch = Some(argument.span()),
+            None => {}
+        }
+        if patch.is_some() {
+            updates.clear();
+            self.script.uses_setters = true;
+            self.script.runtime_imports.insert("applyData");
+        }
+        for update in &updates {
+            self.script.state.insert(update.root.clone());
+            if !update.path.is_empty() {
+                self.script.runtime_imports.insert("setIn");
+            }
+        }
+        let callback = call.arguments.get(1).map(|argument| argument.span());
+        if let Some(callback) = callback {
+            self.warn(&self.slice(callback), "setData callback is deferred until after the state update");
+        }
+        let span = statement.unwrap_or(call.span);
+        let statement = statement.map(|span| self.indent_at(span.start));
+        self.edits.push((span, Edit::SetData { updates, patch, callback, statement }));
+        for argument in &call.arguments {
+            self.visit_argument(argument);
+        }
+        true
+    }
+}
+
+impl<'s, 'a> Visit<'a> for Extractor<'s, 'a> {
+    fn visit_statements(&mut self, it: &oxc_allocator::Vec<'a, Statement<'a>>) {
+        for statement in it {
+            if let Statement::ExpressionStatement(statement) = statement {
+                self.list_statements.insert(statement.span.start);
+            }
+        }
+        walk::walk_statements(self, it);
+    }
+
+    fn visit_arrow_function_expression(&mut self, it: &ArrowFunctionExpression<'a>) {
+        self.mark_expression_body(it);
+        walk::walk_arrow_function_expression(self, it);
+    }
+
+    // 只有块中的 setData 语句可以展开为多条语句，if 的单语句分支和箭头函数的表达式体按表达式展开
+    fn visit_expression_statement(&mut self, it: &ExpressionStatement<'a>) {
+        let in_list = self.list_statements.contains(&it.span.start) && !self.expression_bodies.contains(&it.span.start);
+        if let Expression::CallExpression(call) = it.expression.without_parentheses() {
+            if in_list && self.set_data(call, Some(it.span)) {
+                return;
+            }
+        }
+        walk::walk_expression_statement(self, it);
+    }
+
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        // 组件内的 getApp() 使用 useApp() 取得的应用实例
+        if is_get_app(it) {
+            self.script.uses_app = true;
+            self.edits.push((it.span, Edit::Text("appInstance".to_string())));
+            return;
+        }
+        if !self.set_data(it, None) {
+            walk::walk_call_expression(self, it);
+        }
+    }
+
+    fn visit_static_member_expression(&mut self, it: &StaticMemberExpression<'a>) {
+        // this.data.x 直接访问状态，this.props.x 访问解构出的同名 prop
+        if let Expression::StaticMemberExpression(object) = &it.object {
+            let name = it.property.name.to_string();
+            if self.is_this_member(object, "data") {
+                self.script.state.insert(name.clone());
+                self.edits.push((it.span, Edit::Text(name)));
+                return;
+            }
+            if self.is_this_member(object, "props") {
+                let text = if self.prop_names.contains(&name) { name } else { format!("props.{}", name) };
+                self.edits.push((it.span, Edit::Text(text)));
+                return;
+            }
+        }
+        // my.createSelectorQuery 由运行时基于 DOM 实现
+        if matches!(&it.object, Expression::Identifier(object) if object.name == "my")
+            && it.property.name == "createSelectorQuery"
+        {
+            self.script.runtime_imports.insert("createSelectorQuery");
+            self.edits.push((it.span, Edit::Text("createSelectorQuery".to_string())));
+            return;
+        }
+        if !self.is_this(&it.object) {
+            walk::walk_static_member_expression(self, it);
+            return;
+        }
+        let name = it.property.name.as_str();
+        let text = match name {
+            "data" => {
+                self.script.uses_data = true;
+                "data".to_string()
+            }
+            // 声明过的 props 已从 props 对象中解构出来，整体访问时使用合并回去的 allProps
+            "props" if self.prop_names.is_empty() => "props".to_string(),
+            "props" => {
+                self.script.uses_props = true;
+                "allProps".to_string()
+            }
+            "setData" => {
+                self.script.uses_setters = true;
+                self.script.runtime_imports.insert("applyData");
+                "((patch: any) => applyData(stateSetters, patch))".to_string()
+            }
+            _ if self.script.ref_fields.contains(name) => format!("{}.current", name),
+            _ if self.members.contains(name) || self.fields.contains(name) => name.to_string(),
+            "$selectComponent" => {
+                self.script.runtime_imports.insert("selectComponent");
+                "selectComponent".to_string()
+            }
+            _ => {
+                if name.starts_with('$') {
+                    self.warn(&format!("this.{}", name), "instance API is not supported");
+                }
+                self.script.uses_instance = true;
+                format!("instance.{}", name)
+            }
+        };
+        self.edits.push((it.span, Edit::Text(text)));
+    }
+
+    fn visit_this_expression(&mut self, it: &ThisExpression) {
+        let line = self.line(it.span);
+        self.warn(&format!("this (line {})", line), "`this` can only be converted in member access");
+    }
+
+    // `const self = this` 记录别名后删除整条声明，之后 self.x 按 this.x 转换
+    fn visit_variable_declaration(&mut self, it: &VariableDeclaration<'a>) {
+        let aliases = it
+            .declarations
+            .iter()
+            .map(|declarator| match (&declarator.id, &declarator.init) {
+                (BindingPattern::BindingIdentifier(id), Some(init))
+                    if matches!(init.get_inner_expression(), Expression::ThisExpression(_)) =>
+                {
+                    Some(id.name.to_string())
+                }
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some(aliases) = aliases else {
+            walk::walk_variable_declaration(self, it);
+            return;
+        };
+        self.this_aliases.extend(aliases);
+        self.edits.push((self.line_span(it.span), Edit::Text(String::new())));
+    }
+
+    fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+        if self.this_aliases.contains(it.name.as_str()) {
+            let line = self.line(it.span);
+            self.warn(&format!("{} (line {})", it.name, line), "`this` can only be converted in member access");
+        }
+    }
+}
+
+/// 选项对象中可以通过 this 调用的函数成员
+fn member_names(kind: ScriptKind, options: &ObjectExpression) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for property in &options.properties {
+        let ObjectPropertyKind::ObjectProperty(property) = property else {
+            continue;
+        };
+        let Some(name) = property.key.static_name() else {
+            continue;
+        };
+        match &property.value {
+            Expression::ObjectExpression(methods) if name == "methods" && kind == ScriptKind::Component => {
+                for method in &methods.properties {
+                    if let ObjectPropertyKind::ObjectProperty(method) = method {
+                        names.extend(method.key.static_name().map(|name| name.to_string()));
+                    }
+                }
+            }
+            Expression::FunctionExpression(_) | Expression::ArrowFunctionExpression(_) if name != "data" => {
+                names.insert(name.to_string());
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Component 选项中 props 声明的属性名
+fn prop_names(options: &ObjectExpression) -> Vec<String> {
+    let props = options.properties.iter().find_map(|property| match property {
+        ObjectPropertyKind::ObjectProperty(property) if property.key.static_name().as_deref() == Some("props") => {
+            match &property.value {
+                Expression::ObjectExpression(props) => Some(props),
+                _ => None,
+            }
+        }
+        _ => None,
+    });
+    props
+        .iter()
+        .flat_map(|props| props.properties.iter())
+        .filter_map(|property| match property {
+            ObjectPropertyKind::ObjectProperty(property) if !property.computed => {
+                property.key.static_name().map(|name| name.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// 由 props 默认值推断 TypeScript 类型，`as`、`satisfies` 和括号按其中的值推断
+fn prop_type(value: &Expression) -> &'static str {
+    match value.get_inner_expression() {
+        Expression::StringLiteral(_) | Expression::TemplateLiteral(_) => "string",
+        Expression::NumericLiteral(_) => "number",
+        Expression::UnaryExpression(unary) if matches!(unary.argument, Expression::NumericLiteral(_)) => "number",
+        Expression::BooleanLiteral(_) => "boolean",
+        Expression::ArrayExpression(_) => "any[]",
+        Expression::ObjectExpression(_) => "Record<string, any>",
+        Expression::FunctionExpression(_) | Expression::ArrowFunctionExpression(_) => "(...args: any[]) => any",
+        _ => "any",
+    }
+}
+
+/// App 选项中的非函数成员
+fn field_names(options: &ObjectExpression) -> HashSet<String> {
+    options
+        .properties
+        .iter()
+        .filter_map(|property| match property {
+            ObjectPropertyKind::ObjectProperty(property)
+                if !matches!(property.value, Expression::FunctionExpression(_) | Expression::ArrowFunctionExpression(_)) =>
+            {
+                property.key.static_name().map(|name| name.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// App 选项中需要保存在 ref 上的字段，对象和数组字段由 useAppData 保存，可以直接修改
+fn ref_field_names(options: &ObjectExpression) -> BTreeSet<String> {
+    options
+        .properties
+        .iter()
+        .filter_map(|property| match property {
+            ObjectPropertyKind::ObjectProperty(property)
+                if !matches!(
+                    property.value.without_parentheses(),
+                    Expression::FunctionExpression(_)
+                        | Expression::ArrowFunctionExpression(_)
+                        | Expression::ObjectExpression(_)
+                        | Expression::ArrayExpression(_)
+                ) =>
+            {
+                property.key.static_name().map(|name| name.to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// data 函数返回的对象字面量中的字段名，返回值不是对象字面量或含有展开、计算属性时返回 None
+fn data_factory_fields(value: &Expression) -> Option<Vec<String>> {
+    let (statements, expression_body) = match value.without_parentheses() {
+        Expression::FunctionExpression(function) => (&function.body.as_ref()?.statements, false),
+        Expression::ArrowFunctionExpression(arrow) => (&arrow.body.statements, arrow.expression),
+        _ => return None,
+    };
+    let mut objects = vec![];
+    for statement in statements {
+        match statement {
+            Statement::ReturnStatement(statement) => objects.push(statement.argument.as_ref()?.without_parentheses()),
+            Statement::ExpressionStatement(statement) if expression_body => {
+                objects.push(statement.expression.without_parentheses());
+            }
+            _ => {}
+        }
+    }
+    if objects.is_empty() {
+        return None;
+    }
+    let mut fields = vec![];
+    for object in objects {
+        let Expression::ObjectExpression(object) = object else {
+            return None;
+        };
+        for property in &object.properties {
+            match property {
+                ObjectPropertyKind::ObjectProperty(property) if !property.computed => {
+                    fields.push(property.key.static_name()?.to_string());
+                }
+                _ => return None,
+            }
+        }
+    }
+    Some(fields)
+}
+
+fn is_get_app(call: &CallExpression) -> bool {
+    matches!(&call.callee, Expression::Identifier(callee) if callee.name == "getApp") && call.arguments.is_empty()
+}
+
+/// 查找顶层语句中的 getApp() 调用
+struct GetAppFinder(bool);
+
+impl<'a> Visit<'a> for GetAppFinder {
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        self.0 |= is_get_app(it);
+        walk::walk_call_expression(self, it);
+    }
+}
+
+
+
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// 状态对应的 setter 名
+pub fn state_setter(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => format!("set{}{}", first.to_uppercase(), chars.as_str()),
+        None => "set".to_string(),
+    }
+}
+
+/// 解析 observers 键中的一个字段，通配符只支持单独的 `**` 和路径末尾的 `.**`
+fn observed_field(field: &str) -> Option<ObservedField> {
+    if field == "**" {
+        return Some(ObservedField::All);
+    }
+    let field = field.strip_suffix(".**").unwrap_or(field);
+    if field.contains('*') {
+        return None;
+    }
+    let (root, path) = parse_data_path(field)?;
+    let mut expr = root.clone();
+    for segment in path {
+        match segment.strip_prefix('"').and_then(|s| s.strip_suffix('"')).filter(|s| is_identifier(s)) {
+            Some(name) => expr.push_str(&format!("?.{}", name)),
+            None => expr.push_str(&format!("?.[{}]", segment)),
+        }
+    }
+    Some(ObservedField::Path(root, expr))
+}
+
+/// 由 observers 的键生成监听函数名，如 `a, b.c` 生成 `observeABC`
+fn observer_name(key: &str) -> String {
+    let mut name = "observe".to_string();
+    for part in key.split(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '$' && c != '*') {
+        let part = if part == "**" { "all" } else { part.trim_matches('*') };
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            name.push(first.to_ascii_uppercase());
+            name.extend(chars);
+        }
+    }
+    name
+}
+
+/// 解析 `list[0].name` 形式的 setData 路径，路径片段输出为 JS 字面量
+fn parse_data_path(key: &str) -> Option<(String, Vec<String>)> {
+    let root_end = key.find(['.', '[']).unwrap_or(key.len());
+    let root = &key[..root_end];
+    if !is_identifier(root) {
+        return None;
+    }
+    let mut path = vec![];
+    let mut rest = &key[root_end..];
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix('.') {
+            let end = tail.find(['.', '[']).unwrap_or(tail.len());
+            if end == 0 {
+                return None;
+            }
+            path.push(quote_string(&tail[..end]));
+            rest = &tail[end..];
+        } else if let Some(tail) = rest.strip_prefix('[') {
+            let end = tail.find(']')?;
+            let index = tail[..end].trim();
+            let quoted = index
+                .strip_prefix('\'')
+                .and_then(|i| i.strip_suffix('\''))
+                .or_else(|| index.strip_prefix('"').and_then(|i| i.strip_suffix('"')));
+            match quoted {
+                Some(name) => path.push(quote_string(name)),
+                None if index.parse::<usize>().is_ok() => path.push(index.to_string()),
+                None => return None,
+            }
+            rest = &tail[end + 1..];
+        } else {
+            return None;
+        }
+    }
+    Some((root.to_string(), path))
+}
+
+/// 识别 `Component({...})` 形式的实例注册语句
+fn registration<'s, 'a>(statement: &'s Statement<'a>) -> Option<(ScriptKind, Option<&'s ObjectExpression<'a>>)> {
+    let Statement::ExpressionStatement(statement) = statement else {
+        return None;
+    };
+    let Expression::CallExpression(call) = statement.expression.without_parentheses() else {
+        return None;
+    };
+    let Expression::Identifier(callee) = &call.callee else {
+        return None;
+    };
+    let kind = ScriptKind::from_callee(callee.name.as_str())?;
+    let options = match call.arguments.first() {
+        Some(Argument::ObjectExpression(object)) => Some(&**object),
+        _ => None,
+    };
+    Some((kind, options))
+}
+
+/// mixin 模块默认导出的选项对象：`export default {}`、`module.exports = {}`，可以包在 `Mixin()` / `Behavior()` 中
+fn mixin_options<'s, 'a>(source: &str, statement: &'s Statement<'a>) -> Option<&'s ObjectExpression<'a>> {
+    let expression = match statement {
+        Statement::ExportDefaultDeclaration(export) => export.declaration.as_expression()?,
+        Statement::ExpressionStatement(statement) => match statement.expression.without_parentheses() {
+            Expression::AssignmentExpression(assign) if assign.left.span().source_text(source) == "module.exports" => {
+                &assign.right
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    match expression.without_parentheses() {
+        Expression::ObjectExpression(object) => Some(object),
+        Expression::CallExpression(call)
+            if matches!(&call.callee, Expression::Identifier(callee) if ["Mixin", "Behavior"].contains(&callee.name.as_str())) =>
+        {
+            match call.arguments.first() {
+                Some(Argument::ObjectExpression(object)) => Some(object),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// 去掉多行片段中除首行外每行的原有缩进
+fn dedent(text: &str, indent: usize) -> String {
+    let mut lines = text.lines();
+    let mut out = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        let width = line.chars().take(indent).take_while(|c| *c == ' ' || *c == '\t').count();
+        out.push('\n');
+        out.push_str(&line[width..]);
+    }
+    out
+}
+
+/// 给多行片段中除首行外的每行增加缩进
+pub fn reindent(text: &str, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| if i == 0 || line.is_empty() { line.to_string() } else { format!("{}{}", pad, line) })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn parse_script_source(
+    source: &str,
+    source_type: SourceType,
+    inherited: &Inherited,
+    mixin: bool,
+) -> Result<Script, String> {
+    let allocator = Allocator::default();
+    let ret = Parser::new(&allocator, source, source_type).parse();
+    if ret.panicked || !ret.errors.is_empty() {
+        let reason = ret.errors.first().map(|error| error.to_string()).unwrap_or_default();
+        return Err(format!("failed to parse script: {}", reason));
+    }
+    let mut extractor = Extractor {
+        source,
+        program: &ret.program,
+        script: Script::default(),
+        members: HashSet::new(),
+        prop_names: vec![],
+        fields: HashSet::new(),
+        inherited: inherited.clone(),
+        mixin,
+        import_sources: HashMap::new(),
+        mixin_imports: HashSet::new(),
+        edits: vec![],
+        list_statements: HashSet::new(),
+        expression_bodies: HashSet::new(),
+        this_aliases: HashSet::new(),
+    };
+    extractor.visit_program();
+    if mixin && extractor.script.kind.is_none() {
+        return Err("mixin must export an options object by default".to_string());
+    }
+    Ok(extractor.script)
+}
+
+fn parse_file(path: &Path, inherited: &Inherited, mixin: bool) -> Result<Script, String> {
+    let source = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let source_type = SourceType::from_path(path).map_err(|e| e.to_string())?;
+    parse_script_source(&source, source_type, inherited, mixin)
+}
+
+/// 解析 js / ts 脚本文件
+pub fn parse_script(path: &Path) -> Result<Script, String> {
+    parse_file(path, &Inherited::default(), false)
+}
+
+/// 解析组件脚本，`inherited` 为 mixins 中声明的成员
+pub fn parse_script_with(path: &Path, inherited: &Inherited) -> Result<Script, String> {
+    parse_file(path, inherited, false)
+}
+
+/// 解析 mixin 模块，`inherited` 为组件和其它 mixins 中声明的成员
+pub fn parse_mixin(path: &Path, inherited: &Inherited) -> Result<Script, String> {
+    parse_file(path, inherited, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(methods: &str) -> Script {
+        let source = format!(
+            "Component({{\n  data: {{ a: 0, b: 0, list: [] }},\n  methods: {{\n{}\n  }},\n}});\n",
+            methods
+        );
+        parse_script_source(&source, SourceType::mjs(), &Inherited::default(), false).unwrap()
+    }
+
+    fn body(script: &Script, name: &str) -> String {
+        script.method(name).map(|method| method.body.clone()).unwrap_or_default()
+    }
+
+    #[test]
+    fn set_data_in_block_expands_to_statements() {
+        let script = component("    tap() {\n      this.setData({ a: 1, b: 2 });\n    },");
+        assert_eq!(body(&script, "tap"), "{\n  setA(1);\n  setB(2);\n}");
+    }
+
+    #[test]
+    fn set_data_in_braceless_branch_stays_one_statement() {
+        let script = component(
+            "    tap(e) {\n      if (e.ok) this.setData({ a: 1, b: 2 }); else this.setData({ a: 3 }, cb);\n    },",
+        );
+        assert_eq!(body(&script, "tap"), "{\n  if (e.ok) (setA(1), setB(2)); else (setA(3), setTimeout(cb));\n}");
+    }
+
+    #[test]
+    fn set_data_in_arrow_expression_body_stays_in_the_arrow() {
+        let script = component(
+            "    tap() {\n      const arrow = () => this.setData({ 'list[0].name': 'x', b: this.data.a });\n    },",
+        );
+        assert_eq!(
+            body(&script, "tap"),
+            "{\n  const arrow = () => (setList((prev: any) => setIn(prev, [0, \"name\"], 'x')), setB(a));\n}"
+        );
+    }
+
+    #[test]
+    fn this_aliases_are_converted_like_this() {
+        let script = component(
+            "    tap() {\n      const self = this;\n      wx.request({\n        success(res) {\n          \
+            self.setData({ a: res.data, b: self.data.b + 1 });\n          self.tap();\n        },\n      });\n    },",
+        );
+        assert_eq!(
+            body(&script, "tap"),
+            concat!(
+                "{\n  wx.request({\n    success(res) {\n",
+                "      setA(res.data);\n      setB(b + 1);\n      tap();\n",
+                "    },\n  });\n}",
+            )
+        );
+        assert!(script.warnings.is_empty());
+    }
+
+    #[test]
+    fn this_alias_outside_of_member_access_is_reported() {
+        let script = component("    tap() {\n      const that = this;\n      register(that);\n    },");
+        assert_eq!(body(&script, "tap"), "{\n  register(that);\n}");
+        assert_eq!(script.warnings.len(), 1);
+    }
+
+    #[test]
+    fn prop_types_unwrap_type_assertions() {
+        let source = "Component({\n  props: {\n    title: 'a' as string,\n    count: (1 satisfies number),\n    \
+            list: ([] as string[]),\n    onTap: (() => {}) as any,\n    extra: null,\n  },\n});\n";
+        let script = parse_script_source(source, SourceType::ts(), &Inherited::default(), false).unwrap();
+        let types = script.props.iter().map(|prop| (prop.name.as_str(), prop.ts_type)).collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [
+                ("title", "string"),
+                ("count", "number"),
+                ("list", "any[]"),
+                ("onTap", "(...args: any[]) => any"),
+                ("extra", "any"),
+            ]
+        );
+    }
+
+    #[test]
+    fn data_factory_fields_become_state() {
+        let source = "Component({\n  data() {\n    const map = {};\n    return { map, count: 1 };\n  },\n});\n";
+        let script = parse_script_source(source, SourceType::mjs(), &Inherited::default(), false).unwrap();
+        assert_eq!(script.state.iter().collect::<Vec<_>>(), ["count", "map"]);
+        assert!(script.warnings.is_empty());
+    }
+
+    #[test]
+    fn data_factory_with_spread_is_reported() {
+        let source = "Component({\n  data: () => ({ ...defaults, count: 1 }),\n});\n";
+        let script = parse_script_source(source, SourceType::mjs(), &Inherited::default(), false).unwrap();
+        assert!(script.state.is_empty());
+        assert_eq!(script.warnings.len(), 1);
+    }
+
+    #[test]
+    fn arrow_method_returns_all_updates() {
+        let script = component("    quick: () => this.setData({ a: 1, b: 2 }),");
+        assert_eq!(body(&script, "quick"), "{\n  return (setA(1), setB(2));\n}");
+    }
+}
@@ -0,0 +1,997 @@
+//! acss / less 样式编译为 css：支持小程序中常用的 less 子集（变量、嵌套、mixin、`@import`、四则运算），
+//! 并将 rpx 换算为可配置的目标单位
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+use regex::Regex;
+use crate::components::builtin_selector;
+
+/// rpx 换算的目标单位，设计稿宽度对应屏幕宽度
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RpxUnit {
+    /// 屏幕宽度为 100vw
+    Vw,
+    /// 屏幕宽度为 10rem，需要配合按屏幕宽度设置根字体大小的适配方案
+    Rem,
+    /// 换算为给定屏幕宽度下的 px
+    Px(f64),
+}
+
+#[derive(Debug, Clone)]
+pub struct StyleOptions {
+    pub rpx_unit: RpxUnit,
+    /// 设计稿宽度，单位 rpx
+    pub design_width: f64,
+    /// 组件样式输出为 CSS Modules，类名只在组件内生效
+    pub css_modules: bool,
+}
+
+impl Default for StyleOptions {
+    fn default() -> Self {
+        StyleOptions { rpx_unit: RpxUnit::Vw, design_width: 750.0, css_modules: false }
+    }
+}
+
+/// 编译后的样式节点
+#[derive(Debug, Clone)]
+pub enum CssNode {
+    Rule { selectors: Vec<String>, declarations: Vec<(String, String)> },
+    /// 带块的 @ 规则，如 `@media`、`@keyframes`、`@font-face`
+    AtRule { prelude: String, declarations: Vec<(String, String)>, children: Vec<CssNode> },
+    /// 不带块的 @ 规则，如 `@charset`
+    Statement(String),
+}
+
+#[derive(Debug, Default)]
+pub struct Stylesheet {
+    pub nodes: Vec<CssNode>,
+    pub warnings: Vec<String>,
+    /// 是否用到了 `:host`，CSS Modules 中改写为组件根节点上的 `.host`
+    pub host: bool,
+}
+
+impl Stylesheet {
+    pub fn to_css(&self) -> String {
+        let mut css = String::new();
+        write_nodes(&self.nodes, 0, &mut css);
+        css
+    }
+
+    /// 选择器中声明的类名，`:global()` 中的除外
+    pub fn class_names(&self) -> BTreeSet<String> {
+        let global = Regex::new(r":global\([^)]*\)").unwrap();
+        let class = Regex::new(r"\.(-?[_a-zA-Z][\w-]*)").unwrap();
+        let mut names = BTreeSet::new();
+        let mut stack = self.nodes.iter().collect::<Vec<_>>();
+        while let Some(node) = stack.pop() {
+            match node {
+                CssNode::Rule { selectors, .. } => {
+                    for selector in selectors {
+                        let selector = global.replace_all(selector, "");
+                        names.extend(class.captures_iter(&selector).map(|cap| cap[1].to_string()));
+                    }
+                }
+                CssNode::AtRule { prelude, children, .. } if !prelude.contains("keyframes") => stack.extend(children),
+                _ => {}
+            }
+        }
+        names
+    }
+}
+
+fn write_nodes(nodes: &[CssNode], indent: usize, css: &mut String) {
+    let pad = " ".repeat(indent);
+    for (i, node) in nodes.iter().enumerate() {
+        if i > 0 && !matches!(node, CssNode::Statement(_)) {
+            css.push('\n');
+        }
+        match node {
+            CssNode::Rule { selectors, declarations } => {
+                css.push_str(&format!("{}{} {{\n", pad, selectors.join(&format!(",\n{}", pad))));
+                write_declarations(declarations, indent + 2, css);
+                css.push_str(&format!("{}}}\n", pad));
+            }
+            CssNode::AtRule { prelude, declarations, children } => {
+                css.push_str(&format!("{}{} {{\n", pad, prelude));
+                write_declarations(declarations, indent + 2, css);
+                write_nodes(children, indent + 2, css);
+                css.push_str(&format!("{}}}\n", pad));
+            }
+            CssNode::Statement(text) => css.push_str(&format!("{}{};\n", pad, text)),
+        }
+    }
+}
+
+fn write_declarations(declarations: &[(String, String)], indent: usize, css: &mut String) {
+    for (name, value) in declarations {
+        css.push_str(&format!("{}{}: {};\n", " ".repeat(indent), name, value));
+    }
+}
+
+/// less 语法树
+#[derive(Debug, Clone)]
+enum Node {
+    Declaration { name: String, value: String },
+    Variable { name: String, value: String },
+    Rule { selector: String, children: Vec<Node> },
+    MixinDefinition { name: String, params: Vec<Param>, children: Vec<Node> },
+    MixinCall { name: String, args: Vec<String> },
+    AtRule { prelude: String, children: Option<Vec<Node>> },
+    Import { path: String },
+}
+
+#[derive(Debug, Clone)]
+struct Param {
+    name: String,
+    default: Option<String>,
+}
+
+/// 去掉注释，字符串和括号内的 `//` 不是注释，如 `url(http://...)`
+fn strip_comments(source: &str) -> String {
+    let chars = source.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    let mut quote = None;
+    let mut parens = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(q) = quote {
+            out.push(c);
+            if c == '\\' && i + 1 < chars.len() {
+                out.push(chars[i + 1]);
+                i += 1;
+            } else if c == q {
+                quote = None;
+            }
+            i += 1;
+            continue;
+        }
+        match (c, chars.get(i + 1)) {
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            ('/', Some('/')) if parens == 0 => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            ('"' | '\'', _) => quote = Some(c),
+            ('(', _) => parens += 1,
+            (')', _) => parens = (parens - 1).max(0),
+            _ => {}
+        }
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    /// 解析到当前块的 `}` 或文件末尾
+    fn parse_nodes(&mut self) -> Vec<Node> {
+        let mut nodes = vec![];
+        loop {
+            let (text, end) = self.read_statement();
+            let text = text.trim();
+            match end {
+                Some('{') => {
+                    let children = self.parse_nodes();
+                    nodes.push(block_node(text, children));
+                }
+                Some('}') | None => {
+                    if !text.is_empty() {
+                        nodes.push(statement_node(text));
+                    }
+                    return nodes;
+                }
+                _ => {
+                    if !text.is_empty() {
+                        nodes.push(statement_node(text));
+                    }
+                }
+            }
+        }
+    }
+
+    /// 读取到顶层的 `;`、`{` 或 `}`，返回读到的文本和结束符
+    fn read_statement(&mut self) -> (String, Option<char>) {
+        let mut text = String::new();
+        let mut quote = None;
+        let mut parens = 0;
+        while let Some(&c) = self.chars.get(self.pos) {
+            self.pos += 1;
+            if let Some(q) = quote {
+                text.push(c);
+                if c == q {
+                    quote = None;
+                }
+                continue;
+            }
+            match c {
+                '"' | '\'' => quote = Some(c),
+                '(' => parens += 1,
+                ')' => parens -= 1,
+                // `@{name}` 插值中的花括号不是块
+                '{' if text.ends_with('@') => {
+                    text.push(c);
+                    while let Some(&c) = self.chars.get(self.pos) {
+                        self.pos += 1;
+                        text.push(c);
+                        if c == '}' {
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                ';' | '{' | '}' if parens <= 0 => return (text, Some(c)),
+                _ => {}
+            }
+            text.push(c);
+        }
+        (text, None)
+    }
+}
+
+fn block_node(prelude: &str, children: Vec<Node>) -> Node {
+    if prelude.starts_with('@') {
+        return Node::AtRule { prelude: prelude.to_string(), children: Some(children) };
+    }
+    let re = Regex::new(r"^([.#][\w-]+)\s*\(([^)]*)\)\s*(when\b.*)?$").unwrap();
+    if let Some(cap) = re.captures(prelude) {
+        let params = split_args(&cap[2])
+            .into_iter()
+            .filter_map(|param| {
+                let (name, default) = match param.split_once(':') {
+                    Some((name, default)) => (name.trim(), Some(default.trim().to_string())),
+                    None => (param.trim(), None),
+                };
+                let name = name.strip_prefix('@')?;
+                Some(Param { name: name.to_string(), default })
+            })
+            .collect();
+        let mut name = cap[1].to_string();
+        // 带条件的 mixin 无法静态求值，名称加上条件使其不会被调用
+        if let Some(guard) = cap.get(3) {
+            name = format!("{} {}", name, guard.as_str());
+        }
+        return Node::MixinDefinition { name, params, children };
+    }
+    Node::Rule { selector: prelude.to_string(), children }
+}
+
+fn statement_node(text: &str) -> Node {
+    if let Some(rest) = text.strip_prefix("@import") {
+        let path = Regex::new(r"^\s*(\([^)]*\))?\s*").unwrap().replace(rest, "");
+        return Node::Import { path: path.trim().to_string() };
+    }
+    let variable = Regex::new(r"(?s)^@([\w-]+)\s*:(.*)$").unwrap();
+    if let Some(cap) = variable.captures(text) {
+        return Node::Variable { name: cap[1].to_string(), value: cap[2].trim().to_string() };
+    }
+    if text.starts_with('@') {
+        return Node::AtRule { prelude: text.to_string(), children: None };
+    }
+    let call = Regex::new(r"(?s)^(?:[.#][\w-]+\s*>?\s*)*?([.#][\w-]+)\s*(?:\((.*)\))?\s*(!important)?$").unwrap();
+    if let Some(cap) = call.captures(text) {
+        let args = cap.get(2).map(|args| split_args(args.as_str())).unwrap_or_default();
+        return Node::MixinCall { name: cap[1].to_string(), args };
+    }
+    match text.split_once(':') {
+        Some((name, value)) => Node::Declaration { name: name.trim().to_string(), value: value.trim().to_string() },
+        None => Node::Declaration { name: text.to_string(), value: String::new() },
+    }
+}
+
+/// 在顶层的分隔符处切分，mixin 参数中出现 `;` 时以 `;` 分隔，否则以 `,` 分隔
+fn split_args(args: &str) -> Vec<String> {
+    let separator = if split_top_level(args, ';').len() > 1 { ';' } else { ',' };
+    split_top_level(args, separator).into_iter().map(|arg| arg.trim().to_string()).filter(|arg| !arg.is_empty()).collect()
+}
+
+fn split_top_level(text: &str, separator: char) -> Vec<String> {
+    let mut parts = vec![];
+    let mut current = String::new();
+    let mut quote = None;
+    let mut parens = 0;
+    for c in text.chars() {
+        match quote {
+            Some(q) => {
+                if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '(' => parens += 1,
+                ')' => parens -= 1,
+                c if c == separator && parens == 0 => {
+                    parts.push(std::mem::take(&mut current));
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        current.push(c);
+    }
+    parts.push(current);
+    parts
+}
+
+/// `@media` 等条件规则嵌套在选择器中时，冒泡到外层并保留父选择器
+const CONDITIONAL_AT_RULES: [&str; 4] = ["@media", "@supports", "@container", "@document"];
+
+/// mixin 调用的最大嵌套深度，防止递归调用
+const MAX_MIXIN_DEPTH: usize = 32;
+
+struct Mixin {
+    params: Vec<Param>,
+    children: Vec<Node>,
+}
+
+struct Compiler<'o> {
+    options: &'o StyleOptions,
+    mixins: HashMap<String, Vec<Mixin>>,
+    /// 变量作用域栈，内层在后
+    frames: Vec<HashMap<String, String>>,
+    mixin_depth: usize,
+    warnings: Vec<String>,
+}
+
+impl Compiler<'_> {
+    fn warn(&mut self, value: &str, reason: &str) {
+        let warning = format!("{}: `{}`", reason, value);
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// 收集 mixin 定义，只含一个类名或 id 的普通规则也可以作为无参数的 mixin 调用
+    fn collect_mixins(&mut self, nodes: &[Node]) {
+        let simple = Regex::new(r"^[.#][\w-]+$").unwrap();
+        for node in nodes {
+            match node {
+                Node::MixinDefinition { name, params, children } => {
+                    if name.contains(" when") {
+                        self.warn(name, "mixin guards are not supported");
+                    }
+                    let mixin = Mixin { params: params.clone(), children: children.clone() };
+                    self.mixins.entry(name.clone()).or_default().push(mixin);
+                    self.collect_mixins(children);
+                }
+                Node::Rule { selector, children } => {
+                    if simple.is_match(selector) {
+                        let mixin = Mixin { params: vec![], children: children.clone() };
+                        self.mixins.entry(selector.clone()).or_default().push(mixin);
+                    }
+                    self.collect_mixins(children);
+                }
+                Node::AtRule { children: Some(children), .. } => self.collect_mixins(children),
+                _ => {}
+            }
+        }
+    }
+
+    fn variable(&self, name: &str) -> Option<String> {
+        self.frames.iter().rev().find_map(|frame| frame.get(name).cloned())
+    }
+
+    /// 编译一个块，声明写入 `declarations`，嵌套规则写入 `out`
+    fn block(
+        &mut self,
+        nodes: &[Node],
+        parents: &[String],
+        declarations: &mut Vec<(String, String)>,
+        out: &mut Vec<CssNode>,
+    ) {
+        // less 变量是惰性求值的，块内的变量在整个块中可见
+        let frame = nodes
+            .iter()
+            .filter_map(|node| match node {
+                Node::Variable { name, value } => Some((name.clone(), value.clone())),
+                _ => None,
+            })
+            .collect();
+        self.frames.push(frame);
+        for node in nodes {
+            match node {
+                Node::Variable { .. } | Node::MixinDefinition { .. } | Node::Import { .. } => {}
+                Node::Declaration { name, value } => {
+                    if name.starts_with('&') {
+                        self.warn(name, "less extend is not supported");
+                        continue;
+                    }
+                    let name = self.interpolate(name);
+                    let value = self.value(value);
+                    declarations.push((name, value));
+                }
+                Node::Rule { selector, children } => {
+                    let selector = self.interpolate(selector);
+                    let selectors = combine_selectors(parents, &selector);
+                    let mut inner = vec![];
+                    let mut nested = vec![];
+                    self.block(children, &selectors, &mut inner, &mut nested);
+                    if !inner.is_empty() {
+                        out.push(CssNode::Rule { selectors, declarations: inner });
+                    }
+                    out.extend(nested);
+                }
+                Node::MixinCall { name, args } => self.call_mixin(name, args, parents, declarations, out),
+                Node::AtRule { prelude, children: None } => out.push(CssNode::Statement(self.prelude(prelude))),
+                Node::AtRule { prelude, children: Some(children) } => {
+                    let prelude = self.prelude(prelude);
+                    let conditional = CONDITIONAL_AT_RULES.iter().any(|rule| prelude.starts_with(rule));
+                    let parents = if conditional { parents } else { &[] };
+                    let mut inner = vec![];
+                    let mut nested = vec![];
+                    self.block(children, parents, &mut inner, &mut nested);
+                    if conditional && !parents.is_empty() && !inner.is_empty() {
+                        let rule = CssNode::Rule { selectors: parents.to_vec(), declarations: std::mem::take(&mut inner) };
+                        nested.insert(0, rule);
+                    }
+                    out.push(CssNode::AtRule { prelude, declarations: inner, children: nested });
+                }
+            }
+        }
+        self.frames.pop();
+    }
+
+    fn call_mixin(
+        &mut self,
+        name: &str,
+        args: &[String],
+        parents: &[String],
+        declarations: &mut Vec<(String, String)>,
+        out: &mut Vec<CssNode>,
+    ) {
+        if self.mixin_depth >= MAX_MIXIN_DEPTH {
+            self.warn(name, "mixin recursion is too deep");
+            return;
+        }
+        let Some(mixins) = self.mixins.get(name) else {
+            self.warn(name, "mixin is not defined");
+            return;
+        };
+        // 参数个数匹配的第一个定义
+        let Some(mixin) = mixins.iter().find(|mixin| {
+            let required = mixin.params.iter().filter(|param| param.default.is_none()).count();
+            args.len() >= required && args.len() <= mixin.params.len()
+        }) else {
+            self.warn(name, "no mixin definition matches the arguments");
+            return;
+        };
+        let params = mixin.params.clone();
+        let children = mixin.children.clone();
+
+        // 参数在调用处求值，支持 `@name: value` 形式的具名参数
+        let mut frame = HashMap::new();
+        let mut positional = vec![];
+        for arg in args {
+            match arg.strip_prefix('@').and_then(|arg| arg.split_once(':')) {
+                Some((name, value)) => {
+                    frame.insert(name.trim().to_string(), self.expand(value.trim()));
+                }
+                None => positional.push(self.expand(arg)),
+            }
+        }
+        let mut positional = positional.into_iter();
+        let mut values = vec![];
+        for param in &params {
+            let value = match frame.get(&param.name) {
+                Some(value) => value.clone(),
+                None => match positional.next() {
+                    Some(value) => value,
+                    None => param.default.as_deref().map(|value| self.expand(value)).unwrap_or_default(),
+                },
+            };
+            values.push(value.clone());
+            frame.insert(param.name.clone(), value);
+        }
+        frame.insert("arguments".to_string(), values.join(" "));
+        self.frames.push(frame);
+        self.mixin_depth += 1;
+        self.block(&children, parents, declarations, out);
+        self.mixin_depth -= 1;
+        self.frames.pop();
+    }
+
+    /// @ 规则的关键字之后按属性值求值
+    fn prelude(&mut self, prelude: &str) -> String {
+        match prelude.split_once(char::is_whitespace) {
+            Some((keyword, rest)) => format!("{} {}", keyword, self.value(rest.trim())),
+            None => prelude.to_string(),
+        }
+    }
+
+    /// 替换选择器、属性名和字符串中的 `@{name}` 插值
+    fn interpolate(&mut self, text: &str) -> String {
+        let re = Regex::new(r"@\{([\w-]+)\}").unwrap();
+        let mut out = String::new();
+        let mut last = 0;
+        for cap in re.captures_iter(text) {
+            let whole = cap.get(0).unwrap();
+            out.push_str(&text[last..whole.start()]);
+            match self.variable(&cap[1]) {
+                Some(value) => {
+                    let value = self.expand(&value);
+                    out.push_str(unquote(&value));
+                }
+                None => {
+                    self.warn(whole.as_str(), "variable is not defined");
+                    out.push_str(whole.as_str());
+                }
+            }
+            last = whole.end();
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+
+    /// 属性值求值：变量替换、`~"..."` 转义、四则运算和 rpx 换算
+    fn value(&mut self, value: &str) -> String {
+        let value = self.expand(value);
+        convert_rpx(&value, self.options)
+    }
+
+    /// 变量和参数的值保留 rpx，与使用处的 rpx 一起运算后再换算
+    fn expand(&mut self, value: &str) -> String {
+        let value = self.interpolate(value);
+        let escape = Regex::new(r#"~"([^"]*)"|~'([^']*)'"#).unwrap();
+        let value = escape
+            .replace_all(&value, |cap: &regex::Captures| cap.get(1).or(cap.get(2)).unwrap().as_str().to_string())
+            .into_owned();
+        let variable = Regex::new(r"@([\w-]+)").unwrap();
+        let mut out = String::new();
+        let mut last = 0;
+        for cap in variable.captures_iter(&value) {
+            let whole = cap.get(0).unwrap();
+            out.push_str(&value[last..whole.start()]);
+            match self.variable(&cap[1]) {
+                // 变量的值在使用处所在的作用域中求值，深度受 mixin 深度限制
+                Some(raw) if self.mixin_depth < MAX_MIXIN_DEPTH => {
+                    self.mixin_depth += 1;
+                    out.push_str(&self.expand(&raw));
+                    self.mixin_depth -= 1;
+                }
+                _ => {
+                    self.warn(whole.as_str(), "variable is not defined");
+                    out.push_str(whole.as_str());
+                }
+            }
+            last = whole.end();
+        }
+        out.push_str(&value[last..]);
+        evaluate_math(&out)
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+/// 嵌套选择器展开，`&` 替换为父选择器，否则作为父选择器的后代
+fn combine_selectors(parents: &[String], selector: &str) -> Vec<String> {
+    let children = split_top_level(selector, ',').into_iter().map(|s| s.trim().to_string()).collect::<Vec<_>>();
+    if parents.is_empty() {
+        return children.into_iter().map(|child| child.replace('&', "").trim().to_string()).collect();
+    }
+    let mut selectors = vec![];
+    for parent in parents {
+        for child in &children {
+            if child.contains('&') {
+                selectors.push(child.replace('&', parent));
+            } else {
+                selectors.push(format!("{} {}", parent, child));
+            }
+        }
+    }
+    selectors
+}
+
+fn parse_dimension(text: &str) -> Option<(f64, &str)> {
+    let re = Regex::new(r"^([+-]?(?:\d+\.?\d*|\.\d+))([a-zA-Z%]*)$").unwrap();
+    let cap = re.captures(text)?;
+    let number = cap[1].parse().ok()?;
+    Some((number, cap.get(2).unwrap().as_str()))
+}
+
+fn format_number(number: f64) -> String {
+    let rounded = (number * 1e6).round() / 1e6;
+    if rounded == rounded.trunc() {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{}", rounded)
+    }
+}
+
+/// 对一组以运算符分隔的操作数求值，单位不兼容或操作数不是数值时返回 None
+fn calculate(tokens: &[String], division: bool) -> Option<String> {
+    let is_op = |token: &str| matches!(token, "+" | "-" | "*") || division && token == "/";
+    if tokens.len() < 3 || tokens.len().is_multiple_of(2) {
+        return None;
+    }
+    let mut operands = vec![];
+    let mut ops = vec![];
+    for (i, token) in tokens.iter().enumerate() {
+        if i % 2 == 0 {
+            let (number, unit) = parse_dimension(token)?;
+            operands.push((number, unit.to_string()));
+        } else if is_op(token) {
+            ops.push(token.as_str());
+        } else {
+            return None;
+        }
+    }
+    let unit_of = |a: &str, b: &str| -> Option<String> {
+        match (a, b) {
+            ("", b) => Some(b.to_string()),
+            (a, "") => Some(a.to_string()),
+            (a, b) if a == b => Some(a.to_string()),
+            _ => None,
+        }
+    };
+    // 先乘除后加减
+    let mut terms = vec![operands[0].clone()];
+    let mut additive = vec![];
+    for (op, operand) in ops.iter().zip(operands.into_iter().skip(1)) {
+        match *op {
+            "*" | "/" => {
+                let (left, left_unit) = terms.pop()?;
+                let unit = unit_of(&left_unit, &operand.1)?;
+                let number = if *op == "*" { left * operand.0 } else { left / operand.0 };
+                terms.push((number, unit));
+            }
+            _ => {
+                additive.push(*op);
+                terms.push(operand);
+            }
+        }
+    }
+    let (mut number, mut unit) = terms[0].clone();
+    for (op, (term, term_unit)) in additive.iter().zip(terms.into_iter().skip(1)) {
+        unit = unit_of(&unit, &term_unit)?;
+        number = if *op == "+" { number + term } else { number - term };
+    }
+    Some(format!("{}{}", format_number(number), unit))
+}
+
+/// 切分运算表达式，`*` 和括号内的 `/` 两侧可以没有空格
+fn math_tokens(text: &str, division: bool) -> Vec<String> {
+    let mut tokens = vec![];
+    for word in text.split_whitespace() {
+        let mut current = String::new();
+        for c in word.chars() {
+            if c == '*' || division && c == '/' {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                tokens.push(c.to_string());
+            } else {
+                current.push(c);
+            }
+        }
+        if !current.is_empty() {
+            tokens.push(current);
+        }
+    }
+    tokens
+}
+
+/// 计算值中的四则运算：不属于函数调用的括号内可以做除法，括号外只计算加减乘
+pub fn evaluate_math(value: &str) -> String {
+    let parens = Regex::new(r"(^|[^\w-])\(([^()]*)\)").unwrap();
+    let mut value = value.to_string();
+    loop {
+        let mut changed = false;
+        let next = parens.replace_all(&value, |cap: &regex::Captures| {
+            match calculate(&math_tokens(&cap[2], true), true) {
+                Some(result) => {
+                    changed = true;
+                    format!("{}{}", &cap[1], result)
+                }
+                None => cap[0].to_string(),
+            }
+        });
+        let next = next.into_owned();
+        if !changed {
+            break;
+        }
+        value = next;
+    }
+    split_top_level(&value, ',')
+        .iter()
+        .map(|part| {
+            let tokens = math_tokens(part, false);
+            let has_op = tokens.iter().any(|token| matches!(token.as_str(), "+" | "-" | "*"));
+            match calculate(&tokens, false).filter(|_| has_op) {
+                Some(result) => {
+                    let leading = &part[..part.len() - part.trim_start().len()];
+                    format!("{}{}", leading, result)
+                }
+                None => part.clone(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// 将值中的 rpx 换算为目标单位
+pub fn convert_rpx(value: &str, options: &StyleOptions) -> String {
+    let re = Regex::new(r"(^|[^\w.-])(-?(?:\d+\.?\d*|\.\d+))rpx\b").unwrap();
+    re.replace_all(value, |cap: &regex::Captures| {
+        let rpx: f64 = cap[2].parse().unwrap_or_default();
+        let converted = match options.rpx_unit {
+            RpxUnit::Vw => format!("{}vw", format_number(rpx * 100.0 / options.design_width)),
+            RpxUnit::Rem => format!("{}rem", format_number(rpx * 10.0 / options.design_width)),
+            RpxUnit::Px(width) => format!("{}px", format_number(rpx * width / options.design_width)),
+        };
+        format!("{}{}", &cap[1], converted)
+    })
+    .into_owned()
+}
+
+/// 将选择器中的小程序标签替换为模板转换后的 DOM 标签或运行时组件的类名
+///
+/// CSS Modules 中运行时组件的类名用 `:global()` 保持原样，`:host` 改写为组件根节点的 `.host`
+pub fn rewrite_selector(selector: &str, css_modules: bool) -> String {
+    let chars = selector.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    let mut quote = None;
+    let mut brackets = 0;
+    // 是否位于复合选择器的开头，只有这里的标识符是标签名
+    let mut compound_start = true;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            out.push(c);
+            i += 1;
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '[' => brackets += 1,
+            ']' => brackets -= 1,
+            ' ' | '>' | '+' | '~' | ',' | '(' if brackets == 0 => {
+                compound_start = true;
+                out.push(c);
+                i += 1;
+                continue;
+            }
+            ':' if css_modules && brackets == 0 && is_host(&chars[i..]) => {
+                out.push_str(".host");
+                i += ":host".len();
+                // `:host(.a)` 的参数与 `.host` 组成复合选择器
+                if chars.get(i) == Some(&'(') {
+                    let end = chars[i..].iter().position(|&c| c == ')').map_or(chars.len(), |end| i + end);
+                    out.extend(&chars[i + 1..end]);
+                    i = (end + 1).min(chars.len());
+                }
+                compound_start = false;
+                continue;
+            }
+            c if compound_start && brackets == 0 && c.is_ascii_alphabetic() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '-' || chars[i] == '_') {
+                    i += 1;
+                }
+                let tag = chars[start..i].iter().collect::<String>();
+                // page 对应运行时 AppLayout 中的页面容器
+                let selector = if tag == "page" { Some(".a-page".to_string()) } else { builtin_selector(&tag) };
+                match selector {
+                    Some(class) if css_modules && class.starts_with('.') => {
+                        out.push_str(&format!(":global({})", class));
+                    }
+                    selector => out.push_str(&selector.unwrap_or(tag)),
+                }
+                compound_start = false;
+                continue;
+            }
+            _ => {}
+        }
+        compound_start = false;
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+fn is_host(chars: &[char]) -> bool {
+    let name = ":host".chars().collect::<Vec<_>>();
+    chars.starts_with(&name)
+        && !chars.get(name.len()).is_some_and(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+}
+
+/// 改写规则中的选择器，`@keyframes` 中的 from、to 不是选择器，返回是否用到了 `:host`
+fn rewrite_selectors(nodes: &mut [CssNode], css_modules: bool) -> bool {
+    let mut host = false;
+    for node in nodes {
+        match node {
+            CssNode::Rule { selectors, .. } => {
+                for selector in selectors {
+                    host |= css_modules && selector.contains(":host");
+                    *selector = rewrite_selector(selector, css_modules);
+                }
+            }
+            CssNode::AtRule { prelude, children, .. } if !prelude.contains("keyframes") => {
+                host |= rewrite_selectors(children, css_modules);
+            }
+            _ => {}
+        }
+    }
+    host
+}
+
+/// 读取样式文件并展开其中引用的 less / acss 文件，css 文件和 url 保留为 `@import` 语句
+fn load(path: &Path, visited: &mut HashSet<PathBuf>, warnings: &mut Vec<String>) -> Vec<Node> {
+    if !visited.insert(path.to_path_buf()) {
+        return vec![];
+    }
+    let source = fs::read_to_string(path).unwrap_or_default();
+    let nodes = Parser { chars: strip_comments(&source).chars().collect(), pos: 0 }.parse_nodes();
+    let mut out = vec![];
+    for node in nodes {
+        let Node::Import { path: import } = &node else {
+            out.push(node);
+            continue;
+        };
+        let target = unquote(import);
+        if target.starts_with("url(") || target.ends_with(".css") || target.contains("://") {
+            out.push(Node::AtRule { prelude: format!("@import {}", import), children: None });
+            continue;
+        }
+        let base = path.parent().unwrap_or(path).join(target);
+        let candidates = [base.clone(), base.with_extension("less"), base.with_extension("acss")];
+        match candidates.into_iter().find(|candidate| candidate.is_file()) {
+            Some(dep) => out.extend(load(&dep, visited, warnings)),
+            None => warnings.push(format!("stylesheet import not found: `{}`", target)),
+        }
+    }
+    out
+}
+
+/// 编译 acss / less 文件
+pub fn compile_style(path: &Path, options: &StyleOptions) -> Stylesheet {
+    let mut warnings = vec![];
+    let nodes = load(path, &mut HashSet::new(), &mut warnings);
+    compile(&nodes, warnings, options)
+}
+
+fn compile(nodes: &[Node], warnings: Vec<String>, options: &StyleOptions) -> Stylesheet {
+    let mut compiler = Compiler { options, mixins: HashMap::new(), frames: vec![], mixin_depth: 0, warnings };
+    compiler.collect_mixins(nodes);
+    let mut declarations = vec![];
+    let mut out = vec![];
+    compiler.block(nodes, &[], &mut declarations, &mut out);
+    for (name, _) in declarations {
+        compiler.warn(&name, "declaration outside of a rule");
+    }
+    let host = rewrite_selectors(&mut out, options.css_modules);
+    Stylesheet { nodes: out, warnings: compiler.warnings, host }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile_source(source: &str, options: &StyleOptions) -> Stylesheet {
+        let nodes = Parser { chars: strip_comments(source).chars().collect(), pos: 0 }.parse_nodes();
+        compile(&nodes, vec![], options)
+    }
+
+    fn css(source: &str) -> String {
+        compile_source(source, &StyleOptions::default()).to_css()
+    }
+
+    #[test]
+    fn variables_are_lazy_and_scoped() {
+        let source = "@color: red;\n.a {\n  color: @color;\n  @color: blue;\n}\n.b { color: @color; }";
+        assert_eq!(css(source), ".a {\n  color: blue;\n}\n\n.b {\n  color: red;\n}\n");
+    }
+
+    #[test]
+    fn nested_rules_join_parent_selectors() {
+        let source = ".a, .b {\n  color: red;\n  &:active { color: blue; }\n  .c { margin: 0; }\n}";
+        assert_eq!(
+            css(source),
+            concat!(
+                ".a,\n.b {\n  color: red;\n}\n\n",
+                ".a:active,\n.b:active {\n  color: blue;\n}\n\n",
+                ".a .c,\n.b .c {\n  margin: 0;\n}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn mixins_expand_with_arguments_and_defaults() {
+        let source = concat!(
+            ".size(@w; @h: @w) { width: @w; height: @h; }\n",
+            ".flex { display: flex; }\n",
+            ".a { .size(10px); .flex; }",
+        );
+        assert_eq!(
+            css(source),
+            ".flex {\n  display: flex;\n}\n\n.a {\n  width: 10px;\n  height: 10px;\n  display: flex;\n}\n"
+        );
+    }
+
+    #[test]
+    fn undefined_mixin_is_reported() {
+        let stylesheet = compile_source(".a { .missing(); }", &StyleOptions::default());
+        assert_eq!(stylesheet.warnings, ["mixin is not defined: `.missing`"]);
+    }
+
+    #[test]
+    fn evaluates_math_outside_of_function_calls() {
+        assert_eq!(evaluate_math("10px + 5px"), "15px");
+        assert_eq!(evaluate_math("(20px / 2) + 4px * 2"), "18px");
+        assert_eq!(evaluate_math("calc(100% - 10px)"), "calc(100% - 10px)");
+        assert_eq!(evaluate_math("1px solid red, 2px + 2px"), "1px solid red, 4px");
+    }
+
+    #[test]
+    fn converts_rpx_to_the_target_unit() {
+        let options = StyleOptions::default();
+        assert_eq!(convert_rpx("75rpx -7.5rpx", &options), "10vw -1vw");
+        assert_eq!(convert_rpx("url(a-10rpx.png)", &options), "url(a-10rpx.png)");
+        let rem = StyleOptions { rpx_unit: RpxUnit::Rem, ..StyleOptions::default() };
+        assert_eq!(convert_rpx("750rpx", &rem), "10rem");
+        let px = StyleOptions { rpx_unit: RpxUnit::Px(375.0), ..StyleOptions::default() };
+        assert_eq!(convert_rpx("20rpx", &px), "10px");
+    }
+
+    #[test]
+    fn rewrites_builtin_tags() {
+        assert_eq!(rewrite_selector("view > text.title", false), "div > span.title");
+        assert_eq!(rewrite_selector("scroll-view [data-view=view]", false), ".a-scroll-view [data-view=view]");
+        assert_eq!(rewrite_selector("page", false), ".a-page");
+    }
+
+    #[test]
+    fn selectors_follow_the_template_mapping() {
+        // 样式与模板共用 BUILTIN_COMPONENTS，每个内置组件的选择器都与模板转换后的节点对应
+        for component in crate::components::BUILTIN_COMPONENTS {
+            let expected = match component.is_runtime() {
+                true => format!(".a-{}", component.name),
+                false => component.jsx_tag().to_string(),
+            };
+            assert_eq!(rewrite_selector(component.name, false), expected);
+        }
+        assert_eq!(css("view, .card text { color: red; }\n@keyframes fade { from { opacity: 0; } }"), concat!(
+            "div,\n.card span {\n  color: red;\n}\n\n",
+            "@keyframes fade {\n  from {\n    opacity: 0;\n  }\n}\n",
+        ));
+    }
+
+    #[test]
+    fn css_modules_keep_runtime_classes_global() {
+        assert_eq!(rewrite_selector("scroll-view .item", true), ":global(.a-scroll-view) .item");
+        assert_eq!(rewrite_selector(":host", true), ".host");
+        assert_eq!(rewrite_selector(":host(.active) view", true), ".host.active div");
+        assert_eq!(rewrite_selector(":hostname", true), ":hostname");
+
+        let options = StyleOptions { css_modules: true, ..StyleOptions::default() };
+        let stylesheet = compile_source("swiper .a { color: red; }\n:host { display: block; }", &options);
+        assert!(stylesheet.host);
+        assert_eq!(stylesheet.class_names().into_iter().collect::<Vec<_>>(), ["a", "host"]);
+    }
+}